@@ -1,4 +1,7 @@
-use clap::Parser;
+use crate::cli::OutputFormat;
+use crate::tui::{SortColumn, SortOrder};
+use clap::{ArgGroup, Parser, Subcommand};
+use std::path::PathBuf;
 
 pub fn get() -> Args {
     Args::parse()
@@ -10,12 +13,158 @@ pub fn get() -> Args {
     about,
     long_about = None,
     after_help = "Examples:\n  \
-                  sam                               # Launch TUI and prompt for App ID\n  \
-                  sam --id 480                      # Launch TUI with App ID 480 (skip prompt)"
+                  sam                               # Launch TUI and pick a game\n  \
+                  sam --id 480                      # Launch TUI with App ID 480 (skip picker)\n  \
+                  sam apps                          # List installed games and their App IDs\n  \
+                  sam list --id 480 --format json   # Print achievements for App ID 480 as JSON\n  \
+                  sam list --id 480 --offline       # Print achievements from the cached schema\n  \
+                  sam unlock --id 480 ACH_WIN       # Unlock an achievement by API name\n  \
+                  sam clear --id 480 --all          # Clear every achievement\n  \
+                  sam snapshot --id 480 -o 480.json # Save which achievements are unlocked\n  \
+                  sam restore 480.json              # Unlock and clear back to a snapshot\n  \
+                  sam plan state.toml               # Show what applying a state file changes\n  \
+                  sam apply state.toml              # Bring every game in a state file in line\n  \
+                  sam reset --id 480 --yes          # Wipe all stats without prompting"
 )]
 pub struct Args {
-    /// Application ID. If provided, skips the App ID prompt in the TUI.
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Application ID. If provided, skips the game picker in the TUI.
     /// Example: --id 480
     #[arg(short, long)]
     pub id: Option<u32>,
+
+    /// Read achievements from a JSON fixture file instead of Steam.
+    /// Example: --fixture achievements.json
+    #[arg(long, value_name = "FILE", global = true)]
+    pub fixture: Option<PathBuf>,
+
+    /// Read achievements and stats from Steam's cached schema without
+    /// initialising the app. Nothing can be changed in this mode.
+    #[arg(long, global = true, conflicts_with = "fixture")]
+    pub offline: bool,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    /// Print achievements without launching the TUI.
+    List {
+        /// Application ID.
+        /// Example: --id 480
+        #[arg(short, long)]
+        id: u32,
+
+        /// Output format.
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+
+        /// Column to sort by. Defaults to the column last used in the TUI.
+        #[arg(short, long, value_enum)]
+        sort: Option<SortColumn>,
+
+        /// Sort order. Defaults to the order last used in the TUI.
+        #[arg(short, long, value_enum)]
+        order: Option<SortOrder>,
+    },
+
+    /// List the games installed in your local Steam libraries.
+    Apps {
+        /// Output format.
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+    },
+
+    /// Unlock achievements without launching the TUI.
+    Unlock(Selection),
+
+    /// Clear achievements without launching the TUI.
+    Clear(Selection),
+
+    /// Reset every stat, and optionally every achievement, for an app.
+    Reset {
+        /// Application ID.
+        /// Example: --id 480
+        #[arg(short, long)]
+        id: u32,
+
+        /// Also reset every achievement.
+        #[arg(long)]
+        include_achievements: bool,
+
+        /// Skip the typed confirmation prompt.
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Save which achievements of an app are unlocked to a JSON file.
+    Snapshot {
+        /// Application ID.
+        /// Example: --id 480
+        #[arg(short, long)]
+        id: u32,
+
+        /// File to write. Prints to stdout if omitted.
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+
+    /// Unlock and clear achievements until they match a snapshot.
+    Restore {
+        /// Snapshot file written by `sam snapshot` or the TUI.
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+    },
+
+    /// Print what `apply` would change to match a desired-state file.
+    Plan {
+        /// TOML file mapping App IDs to achievement patterns, e.g.
+        /// 480 = { unlock = ["ACH_*"], clear = ["ACH_WIN_ONE_GAME"] }
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+    },
+
+    /// Unlock and clear achievements to match a desired-state file.
+    Apply {
+        /// TOML file mapping App IDs to achievement patterns, e.g.
+        /// 480 = { unlock = ["ACH_*"], clear = ["ACH_WIN_ONE_GAME"] }
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+    },
+
+    /// Serve requests for one app as line-delimited JSON over stdio.
+    /// The TUI runs one of these per game it loads.
+    #[command(hide = true)]
+    Worker {
+        /// Application ID.
+        #[arg(short, long)]
+        id: u32,
+    },
+}
+
+#[derive(clap::Args, Debug, Clone)]
+#[command(group(
+    ArgGroup::new("targets")
+        .required(true)
+        .multiple(true)
+        .args(["names", "all", "from_file"])
+))]
+pub struct Selection {
+    /// Application ID.
+    /// Example: --id 480
+    #[arg(short, long)]
+    pub id: u32,
+
+    /// Achievement API names.
+    #[arg(value_name = "NAME")]
+    pub names: Vec<String>,
+
+    /// Apply to every achievement of the app.
+    #[arg(long, conflicts_with_all = ["names", "from_file"])]
+    pub all: bool,
+
+    /// Read achievement API names from a file, one per line. Use - for stdin.
+    /// Blank lines and lines starting with # are ignored.
+    #[arg(long, value_name = "FILE")]
+    pub from_file: Option<PathBuf>,
 }
@@ -0,0 +1,46 @@
+use super::{OutputFormat, csv_field};
+use crate::library::{self, InstalledApp};
+use anyhow::Result;
+
+pub fn run(format: OutputFormat) -> Result<()> {
+    let apps = library::installed_apps()?;
+
+    match format {
+        OutputFormat::Table => print_table(&apps),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&apps)?),
+        OutputFormat::Csv => print_csv(&apps),
+    }
+
+    Ok(())
+}
+
+fn print_table(apps: &[InstalledApp]) {
+    let name_width = apps
+        .iter()
+        .map(|a| a.name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("Name".len());
+
+    println!("{:>10}  {:<name_width$}  Install Dir", "App ID", "Name");
+    for app in apps {
+        println!(
+            "{:>10}  {:<name_width$}  {}",
+            app.id,
+            app.name,
+            app.install_dir.display()
+        );
+    }
+}
+
+fn print_csv(apps: &[InstalledApp]) {
+    println!("id,name,install_dir");
+    for app in apps {
+        println!(
+            "{},{},{}",
+            app.id,
+            csv_field(&app.name),
+            csv_field(&app.install_dir.to_string_lossy())
+        );
+    }
+}
@@ -0,0 +1,92 @@
+use super::{OutputFormat, csv_field};
+use crate::steam::{AchievementInfo, StatsBackend};
+use crate::time;
+use crate::tui::{self, ConfigFile, SortColumn, SortOrder};
+use anyhow::Result;
+
+/// Shown in the Global column until Steam has reported percentages.
+const UNKNOWN_PERCENTAGE: &str = "—";
+
+pub fn run(
+    backend: &mut dyn StatsBackend,
+    id: u32,
+    format: OutputFormat,
+    sort: Option<SortColumn>,
+    order: Option<SortOrder>,
+) -> Result<()> {
+    let (ConfigFile { config, .. }, warning) = ConfigFile::load();
+    if let Some(warning) = warning {
+        eprintln!("Warning: {}", warning);
+    }
+    let sort = sort.unwrap_or(config.sort_column);
+    let order = order.unwrap_or(config.sort_order);
+
+    let mut achievements = backend.get_achievements(id)?.achievements;
+    tui::sort(&mut achievements, &sort, &order);
+
+    match format {
+        OutputFormat::Table => print_table(&achievements),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&achievements)?),
+        OutputFormat::Csv => print_csv(&achievements),
+    }
+
+    Ok(())
+}
+
+fn print_table(achievements: &[AchievementInfo]) {
+    let name_width = achievements
+        .iter()
+        .map(|a| a.name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("API Name".len());
+    let title_width = achievements
+        .iter()
+        .map(|a| a.display_name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("Name".len());
+
+    println!(
+        "{:<6} {:>7}  {:<16}  {:<name_width$}  {:<title_width$}  Description",
+        "Done", "Global", "Unlocked", "API Name", "Name"
+    );
+    for achievement in achievements {
+        println!(
+            "{:<6} {:>7}  {:<16}  {:<name_width$}  {:<title_width$}  {}",
+            if achievement.unlocked { "[✓]" } else { "[ ]" },
+            achievement
+                .percentage
+                .map_or(UNKNOWN_PERCENTAGE.to_string(), |p| format!("{:.1}%", p)),
+            achievement
+                .unlocked_at
+                .map(time::format_timestamp)
+                .unwrap_or_default(),
+            achievement.name,
+            achievement.display_name,
+            achievement.description
+        );
+    }
+}
+
+fn print_csv(achievements: &[AchievementInfo]) {
+    println!("name,display_name,description,hidden,unlocked,unlocked_at,percentage");
+    for achievement in achievements {
+        println!(
+            "{},{},{},{},{},{},{}",
+            csv_field(&achievement.name),
+            csv_field(&achievement.display_name),
+            csv_field(&achievement.description),
+            achievement.hidden,
+            achievement.unlocked,
+            achievement
+                .unlocked_at
+                .map(|t| t.to_string())
+                .unwrap_or_default(),
+            achievement
+                .percentage
+                .map(|p| p.to_string())
+                .unwrap_or_default()
+        );
+    }
+}
@@ -0,0 +1,51 @@
+mod apps;
+mod list;
+mod plan;
+mod process;
+mod reset;
+mod snapshot;
+mod worker;
+
+use crate::args::Command;
+use crate::steam::StatsBackend;
+use anyhow::Result;
+use clap::ValueEnum;
+
+#[derive(Clone, Copy, PartialEq, Debug, ValueEnum)]
+pub enum OutputFormat {
+    Table,
+    Json,
+    Csv,
+}
+
+pub fn run(command: Command, backend: &mut dyn StatsBackend) -> Result<()> {
+    match command {
+        Command::List {
+            id,
+            format,
+            sort,
+            order,
+        } => list::run(backend, id, format, sort, order),
+        Command::Apps { format } => apps::run(format),
+        Command::Unlock(selection) => process::run(backend, selection, false),
+        Command::Clear(selection) => process::run(backend, selection, true),
+        Command::Reset {
+            id,
+            include_achievements,
+            yes,
+        } => reset::run(backend, id, include_achievements, yes),
+        Command::Snapshot { id, output } => snapshot::save(backend, id, output),
+        Command::Restore { file } => snapshot::restore(backend, &file),
+        Command::Plan { file } => plan::plan(backend, &file),
+        Command::Apply { file } => plan::apply(backend, &file),
+        Command::Worker { id } => worker::run(backend, id),
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
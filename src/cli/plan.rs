@@ -0,0 +1,94 @@
+use super::process::apply_changes;
+use crate::desired::{DesiredState, Plan};
+use crate::steam::StatsBackend;
+use anyhow::{Result, bail};
+use std::path::Path;
+
+/// Prints what `apply` would change for every game in the state file.
+pub fn plan(backend: &mut dyn StatsBackend, path: &Path) -> Result<()> {
+    let plans = load_plans(backend, path)?;
+
+    let mut to_set = 0;
+    let mut to_clear = 0;
+
+    for (id, plan) in &plans {
+        print_plan(*id, plan);
+        to_set += plan.changes.to_set.len();
+        to_clear += plan.changes.to_clear.len();
+    }
+
+    println!(
+        "Plan: {} to unlock, {} to clear across {} app(s)",
+        to_set,
+        to_clear,
+        plans.len()
+    );
+
+    Ok(())
+}
+
+/// Brings every game in the state file to its desired state.
+pub fn apply(backend: &mut dyn StatsBackend, path: &Path) -> Result<()> {
+    let plans = load_plans(backend, path)?;
+
+    let mut total = 0;
+    let mut failed = 0;
+
+    for (id, plan) in plans {
+        print_unmatched(id, &plan);
+
+        if plan.changes.is_empty() {
+            println!("App {}: no changes", id);
+            continue;
+        }
+
+        println!("App {}:", id);
+        let (processed, failures) = apply_changes(backend, id, plan.changes);
+        total += processed;
+        failed += failures;
+    }
+
+    if failed > 0 {
+        bail!("Processed: {} success, {} failed", total - failed, failed);
+    }
+
+    Ok(())
+}
+
+/// Reads the state file and diffs each game against Steam, before anything
+/// is changed so a bad entry late in the file doesn't leave a partial apply.
+fn load_plans(backend: &mut dyn StatsBackend, path: &Path) -> Result<Vec<(u32, Plan)>> {
+    let state = DesiredState::load(path)?;
+
+    state
+        .games
+        .iter()
+        .map(|(&id, game)| {
+            let live = backend.get_achievements(id)?.achievements;
+            Ok((id, game.plan(&live)))
+        })
+        .collect()
+}
+
+fn print_plan(id: u32, plan: &Plan) {
+    print_unmatched(id, plan);
+
+    if plan.changes.is_empty() {
+        println!("App {}: no changes", id);
+        return;
+    }
+
+    println!("App {}:", id);
+    for name in &plan.changes.to_set {
+        println!("  + {}", name);
+    }
+    for name in &plan.changes.to_clear {
+        println!("  - {}", name);
+    }
+}
+
+fn print_unmatched(id: u32, plan: &Plan) {
+    for pattern in &plan.unmatched {
+        eprintln!("Warning: {} matches no achievements of app {}", pattern, id);
+    }
+}
@@ -0,0 +1,113 @@
+use crate::args::Selection;
+use crate::steam::{Changes, StatsBackend, process_achievements};
+use anyhow::{Context, Result, bail};
+use std::fs;
+use std::io::{self, Read};
+
+pub fn run(backend: &mut dyn StatsBackend, selection: Selection, clear: bool) -> Result<()> {
+    let id = selection.id;
+    let names = resolve_names(backend, selection)?;
+
+    if names.is_empty() {
+        bail!("No achievements to process");
+    }
+
+    let (results, stored) = process_achievements(backend, id, names, clear);
+
+    // NOTE: Nothing sticks if the store failed, so every name is reported as
+    // failed rather than showing ✓ for changes that were lost
+    let saved = stored.is_ok();
+    let failed = results.iter().filter(|r| !r.success).count();
+    for result in &results {
+        let ok = result.success && saved;
+        println!("{} {}", if ok { "✓" } else { "✗" }, result.name);
+    }
+
+    stored.context("None of the changes were saved")?;
+
+    if failed > 0 {
+        bail!(
+            "Processed: {} success, {} failed",
+            results.len() - failed,
+            failed
+        );
+    }
+
+    Ok(())
+}
+
+/// Unlocks then clears `changes`, printing each result. Returns how many
+/// achievements were processed and how many of those failed, counting every
+/// achievement in a batch whose store failed.
+pub(super) fn apply_changes(
+    backend: &mut dyn StatsBackend,
+    id: u32,
+    changes: Changes,
+) -> (usize, usize) {
+    let mut total = 0;
+    let mut failed = 0;
+
+    for (names, clear) in [(changes.to_set, false), (changes.to_clear, true)] {
+        if names.is_empty() {
+            continue;
+        }
+
+        let (results, stored) = process_achievements(backend, id, names, clear);
+
+        let saved = stored.is_ok();
+        for result in &results {
+            let ok = result.success && saved;
+            println!(
+                "{} {} {}",
+                if ok { "✓" } else { "✗" },
+                if clear { "clear " } else { "unlock" },
+                result.name
+            );
+        }
+
+        total += results.len();
+        match stored {
+            Ok(()) => failed += results.iter().filter(|r| !r.success).count(),
+            Err(e) => {
+                eprintln!("✗ Failed to store changes for app {}: {:#}", id, e);
+                failed += results.len();
+            }
+        }
+    }
+
+    (total, failed)
+}
+
+fn resolve_names(backend: &mut dyn StatsBackend, selection: Selection) -> Result<Vec<String>> {
+    if selection.all {
+        return Ok(backend
+            .get_achievements(selection.id)?
+            .achievements
+            .into_iter()
+            .map(|a| a.name)
+            .collect());
+    }
+
+    let mut names = selection.names;
+
+    if let Some(path) = selection.from_file {
+        let contents = if path.as_os_str() == "-" {
+            let mut buffer = String::new();
+            io::stdin().read_to_string(&mut buffer)?;
+            buffer
+        } else {
+            fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?
+        };
+
+        names.extend(
+            contents
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(str::to_string),
+        );
+    }
+
+    Ok(names)
+}
@@ -0,0 +1,39 @@
+use crate::steam::{RESET_CONFIRMATION, StatsBackend};
+use anyhow::{Result, bail};
+use std::io::{self, Write};
+
+pub fn run(
+    backend: &mut dyn StatsBackend,
+    id: u32,
+    include_achievements: bool,
+    yes: bool,
+) -> Result<()> {
+    if !yes {
+        let target = if include_achievements {
+            "stats and achievements"
+        } else {
+            "stats"
+        };
+        print!(
+            "This wipes all {} for app {}. Type {} to confirm: ",
+            target, id, RESET_CONFIRMATION
+        );
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        if input.trim() != RESET_CONFIRMATION {
+            bail!("Aborted, nothing was reset");
+        }
+    }
+
+    backend.reset_all_stats(id, include_achievements)?;
+
+    if include_achievements {
+        println!("✓ Reset all stats and achievements for app {}", id);
+    } else {
+        println!("✓ Reset all stats for app {}", id);
+    }
+
+    Ok(())
+}
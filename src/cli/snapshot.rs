@@ -0,0 +1,52 @@
+use super::process::apply_changes;
+use crate::snapshot::Snapshot;
+use crate::steam::StatsBackend;
+use crate::time;
+use anyhow::{Result, bail};
+use std::path::{Path, PathBuf};
+
+pub fn save(backend: &mut dyn StatsBackend, id: u32, output: Option<PathBuf>) -> Result<()> {
+    let achievements = backend.get_achievements(id)?.achievements;
+    let snapshot = Snapshot::capture(id, &achievements);
+
+    match output {
+        Some(path) => {
+            snapshot.save(&path)?;
+            let unlocked = achievements.iter().filter(|a| a.unlocked).count();
+            eprintln!(
+                "Saved {}/{} unlocked achievements to {}",
+                unlocked,
+                achievements.len(),
+                path.display()
+            );
+        }
+        None => println!("{}", snapshot.to_json()?),
+    }
+
+    Ok(())
+}
+
+pub fn restore(backend: &mut dyn StatsBackend, path: &Path) -> Result<()> {
+    let snapshot = Snapshot::load(path)?;
+    let id = snapshot.app_id;
+
+    let live = backend.get_achievements(id)?.achievements;
+    let changes = snapshot.diff(&live);
+
+    if changes.is_empty() {
+        println!(
+            "App {} already matches the snapshot from {}",
+            id,
+            time::format_timestamp(snapshot.created_at)
+        );
+        return Ok(());
+    }
+
+    let (total, failed) = apply_changes(backend, id, changes);
+
+    if failed > 0 {
+        bail!("Processed: {} success, {} failed", total - failed, failed);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,12 @@
+use crate::steam::{StatsBackend, serve};
+use anyhow::{Context, Result};
+use filedescriptor::{FileDescriptor, StdioDescriptor};
+use std::io::{self, BufWriter};
+
+pub fn run(backend: &mut dyn StatsBackend, id: u32) -> Result<()> {
+    // NOTE: Steamworks prints to stdout on its own, so responses go to the
+    // original stdout while anything else written there ends up on stderr
+    let output = FileDescriptor::redirect_stdio(&io::stderr(), StdioDescriptor::Stdout)
+        .context("Failed to move stdout out of the way of responses")?;
+    serve(backend, id, io::stdin().lock(), BufWriter::new(output))
+}
@@ -0,0 +1,158 @@
+use crate::steam::{AchievementInfo, Changes};
+use anyhow::{Context, Result, anyhow};
+use glob::Pattern;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// The achievements wanted for one or more games, read from TOML:
+///
+/// ```toml
+/// 480 = { unlock = ["ACH_*"], clear = ["ACH_WIN_ONE_GAME"] }
+/// ```
+#[derive(Debug)]
+pub struct DesiredState {
+    pub games: BTreeMap<u32, GameState>,
+}
+
+/// Glob patterns over API names. Anything matching `clear` is cleared, even
+/// if it also matches `unlock`; anything matching neither is left alone.
+#[derive(Debug)]
+pub struct GameState {
+    pub unlock: Vec<Pattern>,
+    pub clear: Vec<Pattern>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawGameState {
+    #[serde(default)]
+    unlock: Vec<String>,
+    #[serde(default)]
+    clear: Vec<String>,
+}
+
+/// What it takes to bring one game to its desired state.
+#[derive(Debug)]
+pub struct Plan {
+    pub changes: Changes,
+    /// Patterns that matched no achievement, which usually means a typo.
+    pub unmatched: Vec<String>,
+}
+
+impl DesiredState {
+    pub fn parse(data: &str) -> Result<Self> {
+        let raw: BTreeMap<String, RawGameState> = toml::from_str(data)?;
+
+        let games = raw
+            .into_iter()
+            .map(|(key, raw)| {
+                let id = key
+                    .parse::<u32>()
+                    .map_err(|_| anyhow!("Invalid App ID: {}", key))?;
+                let state = GameState {
+                    unlock: patterns(&raw.unlock)?,
+                    clear: patterns(&raw.clear)?,
+                };
+                Ok((id, state))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self { games })
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let data = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::parse(&data).with_context(|| format!("Invalid state file {}", path.display()))
+    }
+}
+
+impl GameState {
+    /// Whether `name` should end up unlocked, or `None` if it isn't covered.
+    pub fn wants(&self, name: &str) -> Option<bool> {
+        if self.clear.iter().any(|p| p.matches(name)) {
+            Some(false)
+        } else if self.unlock.iter().any(|p| p.matches(name)) {
+            Some(true)
+        } else {
+            None
+        }
+    }
+
+    pub fn plan(&self, live: &[AchievementInfo]) -> Plan {
+        let changes = Changes::diff(live, |name| self.wants(name));
+
+        let unmatched = self
+            .unlock
+            .iter()
+            .chain(&self.clear)
+            .filter(|p| !live.iter().any(|a| p.matches(&a.name)))
+            .map(|p| p.as_str().to_string())
+            .collect();
+
+        Plan { changes, unmatched }
+    }
+}
+
+fn patterns(sources: &[String]) -> Result<Vec<Pattern>> {
+    sources
+        .iter()
+        .map(|source| Pattern::new(source).with_context(|| format!("Invalid pattern: {}", source)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::steam::achievement;
+
+    #[test]
+    fn parses_games_by_app_id() {
+        let state = DesiredState::parse(
+            r#"
+            480 = { unlock = ["ACH_*"], clear = ["ACH_WIN_ONE_GAME"] }
+
+            [620]
+            clear = ["*"]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(state.games.len(), 2);
+        assert_eq!(state.games[&480].unlock.len(), 1);
+        assert!(state.games[&620].unlock.is_empty());
+    }
+
+    #[test]
+    fn rejects_bad_input() {
+        let error = DesiredState::parse("portal = { unlock = [] }").unwrap_err();
+        assert_eq!(error.to_string(), "Invalid App ID: portal");
+
+        let error = DesiredState::parse("480 = { unlock = [\"[\"] }").unwrap_err();
+        assert_eq!(error.to_string(), "Invalid pattern: [");
+
+        assert!(DesiredState::parse("480 = { unlcok = [] }").is_err());
+    }
+
+    #[test]
+    fn clear_wins_over_unlock() {
+        let state = DesiredState::parse(
+            r#"480 = { unlock = ["ACH_*", "NOPE_*"], clear = ["ACH_WIN_ONE_GAME"] }"#,
+        )
+        .unwrap();
+
+        let live = [
+            achievement("ACH_WIN_ONE_GAME", true),
+            achievement("ACH_TRAVEL_FAR", false),
+            achievement("ACH_WIN_100_GAMES", true),
+            achievement("OTHER", true),
+        ];
+
+        let plan = state.games[&480].plan(&live);
+        assert_eq!(plan.changes.to_set, ["ACH_TRAVEL_FAR"]);
+        assert_eq!(plan.changes.to_clear, ["ACH_WIN_ONE_GAME"]);
+        assert_eq!(plan.unmatched, ["NOPE_*"]);
+    }
+}
@@ -0,0 +1,101 @@
+use super::Value;
+use anyhow::{Result, bail};
+
+const TYPE_SECTION: u8 = 0;
+const TYPE_STRING: u8 = 1;
+const TYPE_INT: u8 = 2;
+const TYPE_FLOAT: u8 = 3;
+const TYPE_POINTER: u8 = 4;
+const TYPE_WIDE_STRING: u8 = 5;
+const TYPE_COLOR: u8 = 6;
+const TYPE_UINT64: u8 = 7;
+const TYPE_END: u8 = 8;
+const TYPE_INT64: u8 = 10;
+const TYPE_END_ALT: u8 = 11;
+
+/// Parses a binary KeyValues document into a root section.
+pub fn parse_binary(data: &[u8]) -> Result<Value> {
+    let mut reader = Reader { data, pos: 0 };
+    let entries = reader.section(true)?;
+    Ok(Value::Section(entries))
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn section(&mut self, root: bool) -> Result<Vec<(String, Value)>> {
+        let mut entries = Vec::new();
+
+        loop {
+            let Some(kind) = self.next_byte() else {
+                // The root section of a file may end without a terminator.
+                if root {
+                    return Ok(entries);
+                }
+                bail!("Unexpected end of KeyValues data at offset {}", self.pos);
+            };
+
+            if kind == TYPE_END || kind == TYPE_END_ALT {
+                return Ok(entries);
+            }
+
+            let key = self.string()?;
+            let value = match kind {
+                TYPE_SECTION => Value::Section(self.section(false)?),
+                TYPE_STRING => Value::String(self.string()?),
+                TYPE_INT => Value::Int(i32::from_le_bytes(self.array()?)),
+                TYPE_FLOAT => Value::Float(f32::from_le_bytes(self.array()?)),
+                TYPE_POINTER => Value::Pointer(i32::from_le_bytes(self.array()?)),
+                TYPE_WIDE_STRING => Value::String(self.wide_string()?),
+                TYPE_COLOR => Value::Color(self.array()?),
+                TYPE_UINT64 => Value::UInt64(u64::from_le_bytes(self.array()?)),
+                TYPE_INT64 => Value::Int64(i64::from_le_bytes(self.array()?)),
+                other => bail!(
+                    "Unknown KeyValues type {} at offset {}",
+                    other,
+                    self.pos - 1
+                ),
+            };
+
+            entries.push((key, value));
+        }
+    }
+
+    fn next_byte(&mut self) -> Option<u8> {
+        let byte = self.data.get(self.pos).copied()?;
+        self.pos += 1;
+        Some(byte)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let Some(bytes) = self.data.get(self.pos..self.pos + N) else {
+            bail!("Unexpected end of KeyValues data at offset {}", self.pos);
+        };
+        self.pos += N;
+        Ok(bytes.try_into()?)
+    }
+
+    fn string(&mut self) -> Result<String> {
+        let rest = &self.data[self.pos..];
+        let Some(len) = rest.iter().position(|&b| b == 0) else {
+            bail!("Unterminated KeyValues string at offset {}", self.pos);
+        };
+        let value = String::from_utf8_lossy(&rest[..len]).into_owned();
+        self.pos += len + 1;
+        Ok(value)
+    }
+
+    fn wide_string(&mut self) -> Result<String> {
+        let mut units = Vec::new();
+        loop {
+            let unit = u16::from_le_bytes(self.array()?);
+            if unit == 0 {
+                return Ok(String::from_utf16_lossy(&units));
+            }
+            units.push(unit);
+        }
+    }
+}
@@ -0,0 +1,62 @@
+mod binary;
+mod text;
+
+pub use binary::parse_binary;
+pub use text::parse_text;
+
+/// A KeyValues node, as used by Valve's VDF and binary VDF formats.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Section(Vec<(String, Value)>),
+    String(String),
+    Int(i32),
+    Float(f32),
+    Pointer(i32),
+    Color([u8; 4]),
+    UInt64(u64),
+    Int64(i64),
+}
+
+impl Value {
+    /// Looks up a child by key, ignoring ASCII case like Steam does.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.entries()
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v)
+    }
+
+    pub fn entries(&self) -> &[(String, Value)] {
+        match self {
+            Value::Section(entries) => entries,
+            _ => &[],
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Numeric value, parsing strings since Steam stores numbers either way.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Value::Int(v) | Value::Pointer(v) => Some(*v as i64),
+            Value::Int64(v) => Some(*v),
+            Value::UInt64(v) => i64::try_from(*v).ok(),
+            Value::Float(v) => Some(*v as i64),
+            Value::String(s) => s.trim().parse().ok(),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Float(v) => Some(*v as f64),
+            Value::String(s) => s.trim().parse().ok(),
+            _ => self.as_i64().map(|v| v as f64),
+        }
+    }
+}
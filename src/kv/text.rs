@@ -0,0 +1,199 @@
+use super::Value;
+use anyhow::{Result, bail};
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// Parses a text KeyValues (VDF) document into a root section.
+pub fn parse_text(input: &str) -> Result<Value> {
+    let mut tokenizer = Tokenizer {
+        chars: input.chars().peekable(),
+        line: 1,
+    };
+    let entries = section(&mut tokenizer, true)?;
+    Ok(Value::Section(entries))
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    String(String),
+    Open,
+    Close,
+}
+
+struct Tokenizer<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+}
+
+impl Tokenizer<'_> {
+    fn next_token(&mut self) -> Result<Option<Token>> {
+        loop {
+            let Some(c) = self.chars.next() else {
+                return Ok(None);
+            };
+
+            match c {
+                '\n' => self.line += 1,
+                c if c.is_whitespace() => {}
+                '/' if self.chars.peek() == Some(&'/') => {
+                    for c in self.chars.by_ref() {
+                        if c == '\n' {
+                            self.line += 1;
+                            break;
+                        }
+                    }
+                }
+                // Platform conditionals such as [$WIN32] are accepted and ignored
+                '[' => {
+                    for c in self.chars.by_ref() {
+                        if c == ']' {
+                            break;
+                        }
+                    }
+                }
+                '{' => return Ok(Some(Token::Open)),
+                '}' => return Ok(Some(Token::Close)),
+                '"' => return self.quoted().map(|s| Some(Token::String(s))),
+                c => return Ok(Some(Token::String(self.unquoted(c)))),
+            }
+        }
+    }
+
+    fn quoted(&mut self) -> Result<String> {
+        let mut value = String::new();
+
+        while let Some(c) = self.chars.next() {
+            match c {
+                '"' => return Ok(value),
+                '\\' => match self.chars.next() {
+                    Some('n') => value.push('\n'),
+                    Some('t') => value.push('\t'),
+                    Some(c) => value.push(c),
+                    None => break,
+                },
+                c => {
+                    if c == '\n' {
+                        self.line += 1;
+                    }
+                    value.push(c);
+                }
+            }
+        }
+
+        bail!("Unterminated string on line {}", self.line)
+    }
+
+    fn unquoted(&mut self, first: char) -> String {
+        let mut value = String::from(first);
+
+        while let Some(&c) = self.chars.peek() {
+            if c.is_whitespace() || matches!(c, '"' | '{' | '}') {
+                break;
+            }
+            value.push(c);
+            self.chars.next();
+        }
+
+        value
+    }
+}
+
+fn section(tokenizer: &mut Tokenizer, root: bool) -> Result<Vec<(String, Value)>> {
+    let mut entries = Vec::new();
+
+    loop {
+        let key = match tokenizer.next_token()? {
+            Some(Token::String(key)) => key,
+            Some(Token::Close) if !root => return Ok(entries),
+            None if root => return Ok(entries),
+            Some(Token::Close) => bail!("Unexpected '}}' on line {}", tokenizer.line),
+            Some(Token::Open) => bail!("Expected a key on line {}", tokenizer.line),
+            None => bail!("Unexpected end of file, missing '}}'"),
+        };
+
+        let value = match tokenizer.next_token()? {
+            Some(Token::String(value)) => Value::String(value),
+            Some(Token::Open) => Value::Section(section(tokenizer, false)?),
+            Some(Token::Close) | None => {
+                bail!("Missing value for \"{}\" on line {}", key, tokenizer.line)
+            }
+        };
+
+        entries.push((key, value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = include_str!("../../tests/fixtures/sample.vdf");
+
+    fn string<'a>(value: &'a Value, path: &[&str]) -> &'a str {
+        path.iter()
+            .try_fold(value, |value, key| value.get(key))
+            .and_then(Value::as_str)
+            .unwrap()
+    }
+
+    #[test]
+    fn parses_escapes_in_quoted_strings() {
+        let root = parse_text(SAMPLE).unwrap();
+        assert_eq!(
+            string(&root, &["sample", "quotes"]),
+            "say \"hi\" to C:\\Steam"
+        );
+        assert_eq!(string(&root, &["sample", "whitespace"]), "one\ntwo\tthree");
+        assert_eq!(string(&root, &["sample", "empty"]), "");
+    }
+
+    #[test]
+    fn skips_comments_but_not_inside_strings() {
+        let root = parse_text(SAMPLE).unwrap();
+        assert_eq!(root.entries().len(), 1);
+        assert_eq!(root.get("sample").unwrap().entries().len(), 8);
+        assert_eq!(
+            string(&root, &["sample", "url"]),
+            "https://store.steampowered.com"
+        );
+    }
+
+    #[test]
+    fn ignores_conditional_blocks() {
+        let root = parse_text(SAMPLE).unwrap();
+        assert_eq!(string(&root, &["sample", "windows"]), "yes");
+        assert_eq!(string(&root, &["sample", "posix"]), "yes");
+        assert_eq!(string(&root, &["sample", "nested", "platform"]), "any");
+    }
+
+    #[test]
+    fn parses_unquoted_tokens() {
+        let root = parse_text(SAMPLE).unwrap();
+        assert_eq!(string(&root, &["sample", "unquoted"]), "value_123");
+        assert_eq!(string(&root, &["sample", "nested", "inline"]), "braces");
+        // Keys are looked up ignoring case, like Steam does
+        assert_eq!(string(&root, &["SAMPLE", "Unquoted"]), "value_123");
+    }
+
+    #[test]
+    fn reports_unterminated_strings() {
+        let error = parse_text("\"key\"\n\"value").unwrap_err();
+        assert_eq!(error.to_string(), "Unterminated string on line 2");
+
+        let error = parse_text("\"key\" \"trailing\\").unwrap_err();
+        assert_eq!(error.to_string(), "Unterminated string on line 1");
+    }
+
+    #[test]
+    fn reports_parse_errors() {
+        let error = |input| parse_text(input).unwrap_err().to_string();
+        assert_eq!(error("\"a\" {"), "Unexpected end of file, missing '}'");
+        assert_eq!(error("\"a\" \"b\"\n}"), "Unexpected '}' on line 2");
+        assert_eq!(error("{ }"), "Expected a key on line 1");
+        assert_eq!(
+            error("\"a\" { \"b\" }"),
+            "Missing value for \"b\" on line 1"
+        );
+        assert_eq!(error("\"a\""), "Missing value for \"a\" on line 1");
+    }
+}
@@ -0,0 +1,206 @@
+use crate::kv::{self, Value};
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Debug, Serialize)]
+pub struct InstalledApp {
+    pub id: u32,
+    pub name: String,
+    pub install_dir: PathBuf,
+}
+
+/// Finds the Steam installation directory.
+///
+/// `STEAM_ROOT` takes precedence over the platform's usual locations.
+pub fn steam_root() -> Option<PathBuf> {
+    if let Some(root) = env::var_os("STEAM_ROOT").filter(|root| !root.is_empty()) {
+        return Some(PathBuf::from(root));
+    }
+
+    let home = env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .map(PathBuf::from);
+
+    let candidates: Vec<PathBuf> = if cfg!(target_os = "windows") {
+        vec![PathBuf::from(r"C:\Program Files (x86)\Steam")]
+    } else if cfg!(target_os = "macos") {
+        home.into_iter()
+            .map(|home| home.join("Library/Application Support/Steam"))
+            .collect()
+    } else {
+        home.into_iter()
+            .flat_map(|home| {
+                [
+                    home.join(".steam/steam"),
+                    home.join(".steam/root"),
+                    home.join(".local/share/Steam"),
+                    home.join(".var/app/com.valvesoftware.Steam/.local/share/Steam"),
+                    home.join("snap/steam/common/.local/share/Steam"),
+                ]
+            })
+            .collect()
+    };
+
+    candidates
+        .into_iter()
+        .find(|path| path.join("steamapps").is_dir())
+}
+
+/// Lists every Steam library folder, starting with the Steam root itself.
+pub fn library_folders(root: &Path) -> Result<Vec<PathBuf>> {
+    let path = root.join("steamapps/libraryfolders.vdf");
+    match fs::read_to_string(&path) {
+        Ok(contents) => parse_library_folders(root, &contents)
+            .with_context(|| format!("Failed to parse {}", path.display())),
+        Err(_) => Ok(vec![root.to_path_buf()]),
+    }
+}
+
+fn parse_library_folders(root: &Path, contents: &str) -> Result<Vec<PathBuf>> {
+    let mut folders = vec![root.to_path_buf()];
+
+    let document = kv::parse_text(contents)?;
+
+    let Some(libraries) = document.get("libraryfolders") else {
+        return Ok(folders);
+    };
+
+    for (key, library) in libraries.entries() {
+        if !key.chars().all(|c| c.is_ascii_digit()) {
+            continue;
+        }
+
+        // Older clients store the path directly, newer ones nest it in a section
+        let folder = match library {
+            Value::String(path) => Some(path.as_str()),
+            _ => library.get("path").and_then(Value::as_str),
+        };
+
+        if let Some(folder) = folder.map(PathBuf::from)
+            && !folders.iter().any(|f| same_folder(f, &folder))
+        {
+            folders.push(folder);
+        }
+    }
+
+    Ok(folders)
+}
+
+/// Enumerates the apps installed across every library folder, sorted by name.
+pub fn installed_apps() -> Result<Vec<InstalledApp>> {
+    let root = steam_root().context("Steam installation not found, set STEAM_ROOT")?;
+
+    let mut apps = BTreeMap::new();
+    for folder in library_folders(&root)? {
+        let Ok(entries) = fs::read_dir(folder.join("steamapps")) else {
+            continue;
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let is_manifest = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("appmanifest_") && name.ends_with(".acf"));
+
+            if is_manifest && let Some(app) = read_manifest(&folder, &path) {
+                apps.entry(app.id).or_insert(app);
+            }
+        }
+    }
+
+    let mut apps: Vec<InstalledApp> = apps.into_values().collect();
+    apps.sort_by_key(|app| app.name.to_lowercase());
+    Ok(apps)
+}
+
+fn read_manifest(folder: &Path, path: &Path) -> Option<InstalledApp> {
+    parse_manifest(folder, &fs::read_to_string(path).ok()?)
+}
+
+fn parse_manifest(folder: &Path, contents: &str) -> Option<InstalledApp> {
+    let document = kv::parse_text(contents).ok()?;
+    let state = document.get("AppState")?;
+
+    let id = u32::try_from(state.get("appid")?.as_i64()?).ok()?;
+    let name = state.get("name")?.as_str()?.to_string();
+    let install_dir = state
+        .get("installdir")
+        .and_then(Value::as_str)
+        .map(|dir| folder.join("steamapps/common").join(dir))
+        .unwrap_or_default();
+
+    Some(InstalledApp {
+        id,
+        name,
+        install_dir,
+    })
+}
+
+fn same_folder(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIBRARY_FOLDERS: &str = include_str!("../tests/fixtures/libraryfolders.vdf");
+    const SPACEWAR_MANIFEST: &str = include_str!("../tests/fixtures/appmanifest_480.acf");
+
+    #[test]
+    fn lists_library_folders_after_the_root() {
+        let root = Path::new("/home/user/.local/share/Steam");
+        let folders = parse_library_folders(root, LIBRARY_FOLDERS).unwrap();
+        assert_eq!(
+            folders,
+            [
+                PathBuf::from("/home/user/.local/share/Steam"),
+                PathBuf::from("/mnt/games/SteamLibrary"),
+            ]
+        );
+    }
+
+    #[test]
+    fn lists_library_folders_in_the_old_format() {
+        let root = Path::new("C:\\Program Files (x86)\\Steam");
+        let contents = r#"
+            "LibraryFolders"
+            {
+                "TimeNextStatsReport"   "1700000000"
+                "ContentStatsID"        "-1234"
+                "1"                     "D:\\SteamLibrary"
+            }
+        "#;
+        let folders = parse_library_folders(root, contents).unwrap();
+        assert_eq!(folders, [root, Path::new("D:\\SteamLibrary")]);
+    }
+
+    #[test]
+    fn reports_broken_library_folders() {
+        let root = Path::new("/steam");
+        let error = parse_library_folders(root, "\"libraryfolders\" {").unwrap_err();
+        assert_eq!(error.to_string(), "Unexpected end of file, missing '}'");
+    }
+
+    #[test]
+    fn reads_app_manifests() {
+        let folder = Path::new("/mnt/games/SteamLibrary");
+        let app = parse_manifest(folder, SPACEWAR_MANIFEST).unwrap();
+        assert_eq!(app.id, 480);
+        assert_eq!(app.name, "Spacewar");
+        assert_eq!(
+            app.install_dir,
+            Path::new("/mnt/games/SteamLibrary/steamapps/common/Spacewar")
+        );
+
+        assert!(parse_manifest(folder, "\"AppState\" { \"name\" \"No ID\" }").is_none());
+    }
+}
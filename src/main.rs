@@ -1,12 +1,68 @@
 mod args;
+mod cli;
+mod desired;
+mod kv;
+mod library;
+mod snapshot;
 mod steam;
+mod time;
 mod tui;
 
+use args::Command;
+use std::ffi::OsString;
+use steam::{MockBackend, OfflineBackend, RemoteBackend, StatsBackend, SteamBackend};
+
 fn main() {
     let args = args::get();
 
-    if let Err(e) = tui::run(args.id) {
-        eprintln!("Error: {}", e);
+    if let Err(e) = run(args) {
+        eprintln!("Error: {:#}", e);
         std::process::exit(1);
     }
 }
+
+fn run(mut args: args::Args) -> anyhow::Result<()> {
+    match args.command.take() {
+        // NOTE: State files can span games, so they get a worker per app too
+        Some(command @ (Command::Plan { .. } | Command::Apply { .. })) => {
+            let mut backend = RemoteBackend::new(worker_args(&args));
+            cli::run(command, &mut backend)
+        }
+        Some(command) => {
+            let mut backend = backend(&args)?;
+            cli::run(command, backend.as_mut())
+        }
+        // NOTE: The TUI can switch games, which needs a fresh process per app
+        None => {
+            let worker_args = worker_args(&args);
+            tui::run(
+                move || Box::new(RemoteBackend::new(worker_args.clone())),
+                args.id,
+            )
+        }
+    }
+}
+
+fn backend(args: &args::Args) -> anyhow::Result<Box<dyn StatsBackend>> {
+    Ok(match &args.fixture {
+        Some(path) => Box::new(MockBackend::from_file(path)?),
+        None if args.offline => Box::new(OfflineBackend::new()),
+        None => Box::new(SteamBackend::new()),
+    })
+}
+
+/// Arguments that make a worker use the same backend as this process.
+fn worker_args(args: &args::Args) -> Vec<OsString> {
+    let mut worker_args = Vec::new();
+
+    if let Some(path) = &args.fixture {
+        worker_args.push("--fixture".into());
+        worker_args.push(path.into());
+    }
+
+    if args.offline {
+        worker_args.push("--offline".into());
+    }
+
+    worker_args
+}
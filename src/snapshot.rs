@@ -0,0 +1,182 @@
+use crate::steam::{AchievementInfo, Changes};
+use crate::time;
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Version of the snapshot file format, bumped on incompatible changes.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// The unlocked set of one app at a point in time.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub version: u32,
+    pub app_id: u32,
+    pub created_at: u64,
+    pub achievements: Vec<SnapshotEntry>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotEntry {
+    pub name: String,
+    pub unlocked: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unlocked_at: Option<u64>,
+}
+
+impl Snapshot {
+    pub fn new(app_id: u32, achievements: Vec<SnapshotEntry>) -> Self {
+        Self {
+            version: SNAPSHOT_VERSION,
+            app_id,
+            created_at: time::now(),
+            achievements,
+        }
+    }
+
+    pub fn capture(app_id: u32, achievements: &[AchievementInfo]) -> Self {
+        Self::new(
+            app_id,
+            achievements
+                .iter()
+                .map(|a| SnapshotEntry {
+                    name: a.name.clone(),
+                    unlocked: a.unlocked,
+                    unlocked_at: a.unlocked_at,
+                })
+                .collect(),
+        )
+    }
+
+    pub fn parse(data: &str) -> Result<Self> {
+        let snapshot: Self = serde_json::from_str(data)?;
+        if snapshot.version > SNAPSHOT_VERSION {
+            bail!(
+                "Snapshot version {} is newer than this sam supports ({})",
+                snapshot.version,
+                SNAPSHOT_VERSION
+            );
+        }
+        Ok(snapshot)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let data = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::parse(&data).with_context(|| format!("Invalid snapshot {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.to_json()? + "\n")
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Whether each achievement should be unlocked, by API name.
+    pub fn unlocked(&self) -> HashMap<&str, bool> {
+        self.achievements
+            .iter()
+            .map(|entry| (entry.name.as_str(), entry.unlocked))
+            .collect()
+    }
+
+    /// What to change in `live` to get back to this snapshot. Achievements
+    /// the snapshot doesn't mention are left alone.
+    pub fn diff(&self, live: &[AchievementInfo]) -> Changes {
+        let wanted = self.unlocked();
+        Changes::diff(live, |name| wanted.get(name).copied())
+    }
+}
+
+/// Where the TUI keeps snapshots, next to its config file.
+pub fn snapshot_dir() -> Result<PathBuf> {
+    let config = confy::get_configuration_file_path("sam", None)?;
+    Ok(config
+        .parent()
+        .map(|dir| dir.join("snapshots"))
+        .unwrap_or_else(|| PathBuf::from("snapshots")))
+}
+
+/// The file a new TUI snapshot of `snapshot.app_id` is saved to.
+pub fn snapshot_path(snapshot: &Snapshot) -> Result<PathBuf> {
+    Ok(snapshot_dir()?.join(format!("{}-{}.json", snapshot.app_id, snapshot.created_at)))
+}
+
+/// The most recent snapshot saved from the TUI for an app, if any.
+pub fn latest_snapshot(app_id: u32) -> Result<Option<PathBuf>> {
+    let dir = snapshot_dir()?;
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return Ok(None);
+    };
+
+    let prefix = format!("{}-", app_id);
+    let latest = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let created_at = name
+                .strip_prefix(&prefix)?
+                .strip_suffix(".json")?
+                .parse::<u64>()
+                .ok()?;
+            Some((created_at, entry.path()))
+        })
+        .max_by_key(|(created_at, _)| *created_at)
+        .map(|(_, path)| path);
+
+    Ok(latest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::steam::achievement;
+
+    #[test]
+    fn round_trips_through_json() {
+        let live = [achievement("ACH_A", true), achievement("ACH_B", false)];
+        let snapshot = Snapshot::capture(480, &live);
+
+        let parsed = Snapshot::parse(&snapshot.to_json().unwrap()).unwrap();
+        assert_eq!(parsed, snapshot);
+        assert_eq!(parsed.version, SNAPSHOT_VERSION);
+    }
+
+    #[test]
+    fn diffs_against_live_state() {
+        let snapshot = Snapshot::capture(
+            480,
+            &[
+                achievement("ACH_A", true),
+                achievement("ACH_B", false),
+                achievement("ACH_C", true),
+            ],
+        );
+
+        let live = [
+            achievement("ACH_A", false),
+            achievement("ACH_B", true),
+            achievement("ACH_C", true),
+            achievement("ACH_NEW", true),
+        ];
+
+        let changes = snapshot.diff(&live);
+        assert_eq!(changes.to_set, ["ACH_A"]);
+        assert_eq!(changes.to_clear, ["ACH_B"]);
+    }
+
+    #[test]
+    fn rejects_newer_versions() {
+        let data = r#"{ "version": 99, "app_id": 480, "created_at": 0, "achievements": [] }"#;
+        let error = Snapshot::parse(data).unwrap_err();
+        assert!(error.to_string().starts_with("Snapshot version 99"));
+    }
+}
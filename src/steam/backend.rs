@@ -0,0 +1,31 @@
+use super::{AchievementData, StatInfo, StatValue};
+use anyhow::Result;
+use std::collections::HashMap;
+
+/// What the user must type before `reset_all_stats` wipes an app's progress.
+pub const RESET_CONFIRMATION: &str = "RESET";
+
+/// Source of achievement state for an app.
+///
+/// Changes made through `set_achievement`, `clear_achievement` and `set_stat`
+/// are only staged until `store_stats` is called, mirroring the Steamworks API.
+pub trait StatsBackend {
+    fn get_achievements(&mut self, id: u32) -> Result<AchievementData>;
+
+    fn set_achievement(&mut self, id: u32, name: &str) -> Result<()>;
+
+    fn clear_achievement(&mut self, id: u32, name: &str) -> Result<()>;
+
+    /// Global unlock percentages keyed by API name, or `None` while Steam is
+    /// still fetching them. Call again later to pick up a late result.
+    fn global_percentages(&mut self, id: u32) -> Result<Option<HashMap<String, f32>>>;
+
+    fn get_stats(&mut self, id: u32) -> Result<Vec<StatInfo>>;
+
+    fn set_stat(&mut self, id: u32, name: &str, value: StatValue) -> Result<()>;
+
+    fn store_stats(&mut self, id: u32) -> Result<()>;
+
+    /// Resets every stat, and optionally every achievement, and stores the result.
+    fn reset_all_stats(&mut self, id: u32, include_achievements: bool) -> Result<()>;
+}
@@ -0,0 +1,269 @@
+use super::schema::load_schema;
+use super::{AchievementData, AchievementInfo, StatInfo, StatKind, StatValue, StatsBackend};
+use anyhow::{Context, Result, anyhow, bail};
+use gag::Gag;
+use std::collections::HashMap;
+use std::ffi::CString;
+use std::sync::{Arc, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+use steamworks::{Client, sys};
+
+/// How long loading waits for global percentages before carrying on without them.
+const PERCENTAGES_TIMEOUT: Duration = Duration::from_secs(2);
+const CALLBACK_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Set once Steam answers a percentages request, to whether it succeeded.
+type PercentagesRequest = Arc<OnceLock<bool>>;
+
+/// Backend that talks to the running Steam client through Steamworks.
+#[derive(Default)]
+pub struct SteamBackend {
+    client: Option<(u32, Client)>,
+    percentages: Option<(u32, PercentagesRequest)>,
+}
+
+impl SteamBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn client(&mut self, id: u32) -> Result<&Client> {
+        let client = match self.client.take() {
+            Some((current, client)) if current == id => client,
+            _ => {
+                let _stdout_gag = Gag::stdout().ok();
+                let _stderr_gag = Gag::stderr().ok();
+
+                Client::init_app(id).with_context(|| format!("App {} not in your library", id))?
+            }
+        };
+
+        Ok(&self.client.insert((id, client)).1)
+    }
+
+    /// Asks Steam for global percentages; the answer arrives through a callback.
+    fn request_percentages(&mut self, id: u32) -> Result<PercentagesRequest> {
+        let request = PercentagesRequest::default();
+        let answer = request.clone();
+
+        self.client(id)?
+            .user_stats()
+            .request_global_achievement_percentages(move |result| {
+                let _ = answer.set(result.is_ok());
+            });
+
+        self.percentages = Some((id, request.clone()));
+        Ok(request)
+    }
+}
+
+impl StatsBackend for SteamBackend {
+    fn get_achievements(&mut self, id: u32) -> Result<AchievementData> {
+        let request = self.request_percentages(id)?;
+        let client = self.client(id)?;
+        let user_stats = client.user_stats();
+
+        match user_stats.get_num_achievements() {
+            Ok(_) => {}
+            Err(_) => bail!("Failed to get achievement names for app {}", id),
+        };
+
+        // NOTE: The callback only fires from run_callbacks
+        let deadline = Instant::now() + PERCENTAGES_TIMEOUT;
+        while request.get().is_none() && Instant::now() < deadline {
+            client.run_callbacks();
+            thread::sleep(CALLBACK_INTERVAL);
+        }
+        let has_percentages = request.get() == Some(&true);
+
+        let achievement_names = match user_stats.get_achievement_names() {
+            Some(x) => x,
+            None => bail!("Failed to get achievement names for app {}", id),
+        };
+
+        // NOTE: Fills in what Steamworks leaves blank for apps it hasn't fully synced
+        let schema = load_schema(id).ok();
+        let language = client.apps().current_game_language();
+
+        let achievements = achievement_names
+            .into_iter()
+            .map(|name| {
+                let achievement = user_stats.achievement(&name);
+
+                let unlocked = achievement.get().unwrap_or(false);
+
+                let unlocked_at = if unlocked {
+                    unlock_time(client, &name)
+                } else {
+                    None
+                };
+
+                let display_name = achievement
+                    .get_achievement_display_attribute("name")
+                    .map(str::to_string)
+                    .unwrap_or_default();
+
+                let description = achievement
+                    .get_achievement_display_attribute("desc")
+                    .map(str::to_string)
+                    .unwrap_or_default();
+
+                let hidden = achievement
+                    .get_achievement_display_attribute("hidden")
+                    .is_ok_and(|value| value == "1");
+
+                let percentage = has_percentages
+                    .then(|| achievement.get_achievement_achieved_percent().ok())
+                    .flatten();
+
+                let mut info = AchievementInfo {
+                    name,
+                    display_name,
+                    description,
+                    hidden,
+                    unlocked,
+                    unlocked_at,
+                    percentage,
+                    icon: None,
+                    icon_gray: None,
+                };
+
+                if let Some(definition) = schema.as_ref().and_then(|s| s.achievement(&info.name)) {
+                    info.enrich(definition, &language);
+                }
+
+                info
+            })
+            .collect();
+
+        Ok(AchievementData { achievements })
+    }
+
+    fn set_achievement(&mut self, id: u32, name: &str) -> Result<()> {
+        self.client(id)?
+            .user_stats()
+            .achievement(name)
+            .set()
+            .map_err(|_| anyhow!("Failed to unlock {}", name))
+    }
+
+    fn clear_achievement(&mut self, id: u32, name: &str) -> Result<()> {
+        self.client(id)?
+            .user_stats()
+            .achievement(name)
+            .clear()
+            .map_err(|_| anyhow!("Failed to clear {}", name))
+    }
+
+    fn global_percentages(&mut self, id: u32) -> Result<Option<HashMap<String, f32>>> {
+        let request = match &self.percentages {
+            Some((current, request)) if *current == id => request.clone(),
+            _ => self.request_percentages(id)?,
+        };
+
+        let client = self.client(id)?;
+        client.run_callbacks();
+
+        match request.get() {
+            None => Ok(None),
+            Some(false) => bail!("Steam couldn't fetch global percentages for app {}", id),
+            Some(true) => {
+                let user_stats = client.user_stats();
+                let names = user_stats.get_achievement_names().unwrap_or_default();
+                let percentages = names
+                    .into_iter()
+                    .filter_map(|name| {
+                        let percentage = user_stats
+                            .achievement(&name)
+                            .get_achievement_achieved_percent()
+                            .ok()?;
+                        Some((name, percentage))
+                    })
+                    .collect();
+                Ok(Some(percentages))
+            }
+        }
+    }
+
+    fn get_stats(&mut self, id: u32) -> Result<Vec<StatInfo>> {
+        let definitions = load_schema(id)?.stats;
+        let user_stats = self.client(id)?.user_stats();
+
+        let stats = definitions
+            .into_iter()
+            .filter_map(|definition| {
+                let value = match definition.kind {
+                    StatKind::Int => {
+                        StatValue::Int(user_stats.get_stat_i32(&definition.name).ok()?)
+                    }
+                    StatKind::Float | StatKind::AvgRate => {
+                        StatValue::Float(user_stats.get_stat_f32(&definition.name).ok()?)
+                    }
+                };
+
+                Some(StatInfo {
+                    name: definition.name,
+                    display_name: definition.display_name,
+                    kind: definition.kind,
+                    value,
+                    min: definition.min,
+                    max: definition.max,
+                    increment_only: definition.increment_only,
+                })
+            })
+            .collect();
+
+        Ok(stats)
+    }
+
+    fn set_stat(&mut self, id: u32, name: &str, value: StatValue) -> Result<()> {
+        let user_stats = self.client(id)?.user_stats();
+        match value {
+            StatValue::Int(v) => user_stats.set_stat_i32(name, v),
+            StatValue::Float(v) => user_stats.set_stat_f32(name, v),
+        }
+        .map_err(|_| anyhow!("Failed to set {}", name))
+    }
+
+    fn store_stats(&mut self, id: u32) -> Result<()> {
+        self.client(id)?
+            .user_stats()
+            .store_stats()
+            .map_err(|_| anyhow!("Failed to store stats to Steam"))
+    }
+
+    fn reset_all_stats(&mut self, id: u32, include_achievements: bool) -> Result<()> {
+        // NOTE: ResetAllStats stores the reset to the server itself
+        self.client(id)?
+            .user_stats()
+            .reset_all_stats(include_achievements)
+            .map_err(|_| anyhow!("Failed to reset stats for app {}", id))
+    }
+}
+
+/// Returns when the current user unlocked `name`, as a Unix timestamp.
+///
+/// The steamworks crate doesn't wrap `GetAchievementAndUnlockTime`, so this
+/// goes through the raw bindings. Taking the `Client` is what guarantees the
+/// API is initialised for the app whose achievements are being read.
+fn unlock_time(_client: &Client, name: &str) -> Option<u64> {
+    let name = CString::new(name).ok()?;
+    let mut achieved = false;
+    let mut unlock_time = 0;
+
+    // SAFETY: The `Client` borrow keeps SteamAPI initialised for the duration
+    // of the call, so the user stats interface pointer is valid. `name` is a
+    // NUL-terminated string that outlives the call, and both out-params point
+    // at live locals of the types the binding expects.
+    let success = unsafe {
+        sys::SteamAPI_ISteamUserStats_GetAchievementAndUnlockTime(
+            sys::SteamAPI_SteamUserStats_v013(),
+            name.as_ptr(),
+            &mut achieved,
+            &mut unlock_time,
+        )
+    };
+
+    (success && achieved && unlock_time > 0).then_some(unlock_time as u64)
+}
@@ -0,0 +1,184 @@
+use super::{AchievementData, AchievementInfo, StatInfo, StatValue, StatsBackend};
+use crate::time;
+use anyhow::{Context, Result, anyhow, bail};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Deterministic in-memory backend, used when Steam is not available.
+///
+/// Fixture files are JSON documents keyed by app ID:
+///
+/// ```json
+/// { "apps": { "480": { "achievements": [
+///     { "name": "ACH_WIN_ONE_GAME", "unlocked": true, "percentage": 42.0 }
+/// ], "failing": ["ACH_TRAVEL_FAR_ACCUM"], "fail_store": false } } }
+/// ```
+#[derive(Debug, Default, Deserialize)]
+pub struct MockBackend {
+    apps: HashMap<u32, MockApp>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MockApp {
+    achievements: Vec<AchievementInfo>,
+    #[serde(default)]
+    stats: Vec<StatInfo>,
+    #[serde(default)]
+    failing: HashSet<String>,
+    #[serde(default)]
+    fail_store: bool,
+    /// Simulated latency for loading, like a slow Steam initialisation.
+    #[serde(default)]
+    delay_ms: u64,
+    /// Holds back global percentages for this long after loading, like a slow
+    /// response from Steam's servers.
+    #[serde(default)]
+    late_percentages_ms: u64,
+    #[serde(skip)]
+    loaded_at: Option<Instant>,
+    #[serde(skip)]
+    staged: HashMap<String, bool>,
+    #[serde(skip)]
+    staged_stats: HashMap<String, StatValue>,
+}
+
+impl MockBackend {
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read fixture {}", path.display()))?;
+        serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse fixture {}", path.display()))
+    }
+
+    fn app(&mut self, id: u32) -> Result<&mut MockApp> {
+        self.apps
+            .get_mut(&id)
+            .ok_or_else(|| anyhow!("App {} not in your library", id))
+    }
+
+    fn stage(&mut self, id: u32, name: &str, unlocked: bool) -> Result<()> {
+        let app = self.app(id)?;
+
+        if app.failing.contains(name) || !app.achievements.iter().any(|a| a.name == name) {
+            bail!("Failed to update {}", name);
+        }
+
+        app.staged.insert(name.to_string(), unlocked);
+        Ok(())
+    }
+}
+
+impl StatsBackend for MockBackend {
+    fn get_achievements(&mut self, id: u32) -> Result<AchievementData> {
+        let app = self.app(id)?;
+        thread::sleep(Duration::from_millis(app.delay_ms));
+        app.loaded_at = Some(Instant::now());
+
+        let mut achievements = app.achievements.clone();
+        if app.late_percentages_ms > 0 {
+            for achievement in achievements.iter_mut() {
+                achievement.percentage = None;
+            }
+        }
+
+        Ok(AchievementData { achievements })
+    }
+
+    fn global_percentages(&mut self, id: u32) -> Result<Option<HashMap<String, f32>>> {
+        let app = self.app(id)?;
+        let late = Duration::from_millis(app.late_percentages_ms);
+        if app
+            .loaded_at
+            .is_some_and(|loaded_at| loaded_at.elapsed() < late)
+        {
+            return Ok(None);
+        }
+
+        Ok(Some(
+            app.achievements
+                .iter()
+                .filter_map(|a| Some((a.name.clone(), a.percentage?)))
+                .collect(),
+        ))
+    }
+
+    fn set_achievement(&mut self, id: u32, name: &str) -> Result<()> {
+        self.stage(id, name, true)
+    }
+
+    fn clear_achievement(&mut self, id: u32, name: &str) -> Result<()> {
+        self.stage(id, name, false)
+    }
+
+    fn get_stats(&mut self, id: u32) -> Result<Vec<StatInfo>> {
+        Ok(self.app(id)?.stats.clone())
+    }
+
+    fn set_stat(&mut self, id: u32, name: &str, value: StatValue) -> Result<()> {
+        let app = self.app(id)?;
+
+        if app.failing.contains(name) || !app.stats.iter().any(|s| s.name == name) {
+            bail!("Failed to set {}", name);
+        }
+
+        app.staged_stats.insert(name.to_string(), value);
+        Ok(())
+    }
+
+    fn store_stats(&mut self, id: u32) -> Result<()> {
+        let app = self.app(id)?;
+
+        if app.fail_store {
+            app.staged.clear();
+            app.staged_stats.clear();
+            bail!("Failed to store stats to Steam");
+        }
+
+        for (name, value) in app.staged_stats.drain() {
+            if let Some(stat) = app.stats.iter_mut().find(|s| s.name == name) {
+                stat.value = value;
+            }
+        }
+
+        for (name, unlocked) in app.staged.drain() {
+            if let Some(achievement) = app.achievements.iter_mut().find(|a| a.name == name)
+                && achievement.unlocked != unlocked
+            {
+                achievement.unlocked = unlocked;
+                achievement.unlocked_at = unlocked.then(time::now);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn reset_all_stats(&mut self, id: u32, include_achievements: bool) -> Result<()> {
+        let app = self.app(id)?;
+        app.staged.clear();
+        app.staged_stats.clear();
+
+        if app.fail_store {
+            bail!("Failed to reset stats for app {}", id);
+        }
+
+        for stat in app.stats.iter_mut() {
+            stat.value = match stat.value {
+                StatValue::Int(_) => StatValue::Int(0),
+                StatValue::Float(_) => StatValue::Float(0.0),
+            };
+        }
+
+        if include_achievements {
+            for achievement in app.achievements.iter_mut() {
+                achievement.unlocked = false;
+                achievement.unlocked_at = None;
+            }
+        }
+
+        Ok(())
+    }
+}
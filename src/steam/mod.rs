@@ -0,0 +1,333 @@
+mod backend;
+mod client;
+mod mock;
+mod offline;
+mod protocol;
+mod remote;
+mod schema;
+
+pub use backend::{RESET_CONFIRMATION, StatsBackend};
+pub use client::SteamBackend;
+pub use mock::MockBackend;
+pub use offline::OfflineBackend;
+pub use protocol::serve;
+pub use remote::RemoteBackend;
+
+use anyhow::{Result, bail};
+use schema::AchievementDefinition;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AchievementInfo {
+    pub name: String,
+    #[serde(default)]
+    pub display_name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub hidden: bool,
+    pub unlocked: bool,
+    #[serde(default)]
+    pub unlocked_at: Option<u64>,
+    /// Share of players who unlocked it, or `None` if Steam hasn't said yet.
+    #[serde(default)]
+    pub percentage: Option<f32>,
+    /// Icon file names from the schema, when it is cached locally.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon_gray: Option<String>,
+}
+
+impl AchievementInfo {
+    /// The localized display name, falling back to the API name.
+    pub fn title(&self) -> &str {
+        if self.display_name.is_empty() {
+            &self.name
+        } else {
+            &self.display_name
+        }
+    }
+
+    /// Fills in anything Steamworks left blank from the cached schema.
+    fn enrich(&mut self, definition: &AchievementDefinition, language: &str) {
+        if self.display_name.is_empty() {
+            self.display_name = definition.display_name(language).to_string();
+        }
+
+        if self.description.is_empty() {
+            self.description = definition.description(language).to_string();
+        }
+
+        self.hidden |= definition.hidden;
+        self.icon = definition.icon.clone();
+        self.icon_gray = definition.icon_gray.clone();
+    }
+}
+
+/// A bare achievement with just a name and unlock state, for tests.
+#[cfg(test)]
+pub fn achievement(name: &str, unlocked: bool) -> AchievementInfo {
+    serde_json::from_value(serde_json::json!({ "name": name, "unlocked": unlocked })).unwrap()
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatKind {
+    Int,
+    Float,
+    AvgRate,
+}
+
+impl fmt::Display for StatKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StatKind::Int => write!(f, "Int"),
+            StatKind::Float => write!(f, "Float"),
+            StatKind::AvgRate => write!(f, "Avg Rate"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum StatValue {
+    Int(i32),
+    Float(f32),
+}
+
+impl StatValue {
+    pub fn as_f64(self) -> f64 {
+        match self {
+            StatValue::Int(v) => v as f64,
+            StatValue::Float(v) => v as f64,
+        }
+    }
+}
+
+impl fmt::Display for StatValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StatValue::Int(v) => write!(f, "{}", v),
+            StatValue::Float(v) => write!(f, "{}", v),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(try_from = "StatFields")]
+pub struct StatInfo {
+    pub name: String,
+    #[serde(default)]
+    pub display_name: String,
+    pub kind: StatKind,
+    pub value: StatValue,
+    #[serde(default)]
+    pub min: Option<f64>,
+    #[serde(default)]
+    pub max: Option<f64>,
+    #[serde(default)]
+    pub increment_only: bool,
+}
+
+/// A stat as written in JSON, before its value is checked against its kind.
+/// Values are untagged, so a float stat written as `3` reads as an integer.
+#[derive(Deserialize)]
+struct StatFields {
+    name: String,
+    #[serde(default)]
+    display_name: String,
+    kind: StatKind,
+    value: StatValue,
+    #[serde(default)]
+    min: Option<f64>,
+    #[serde(default)]
+    max: Option<f64>,
+    #[serde(default)]
+    increment_only: bool,
+}
+
+impl TryFrom<StatFields> for StatInfo {
+    type Error = String;
+
+    fn try_from(fields: StatFields) -> Result<Self, Self::Error> {
+        let value = match (fields.kind, fields.value) {
+            (StatKind::Int, StatValue::Int(v)) => StatValue::Int(v),
+            (StatKind::Int, StatValue::Float(v)) => {
+                return Err(format!(
+                    "{} is an int stat but has value {}",
+                    fields.name, v
+                ));
+            }
+            (StatKind::Float | StatKind::AvgRate, value) => StatValue::Float(value.as_f64() as f32),
+        };
+
+        Ok(Self {
+            name: fields.name,
+            display_name: fields.display_name,
+            kind: fields.kind,
+            value,
+            min: fields.min,
+            max: fields.max,
+            increment_only: fields.increment_only,
+        })
+    }
+}
+
+impl StatInfo {
+    pub fn is_editable(&self) -> bool {
+        self.kind != StatKind::AvgRate
+    }
+
+    /// Parses and validates a new value for this stat against its schema.
+    pub fn parse_value(&self, input: &str) -> Result<StatValue> {
+        let input = input.trim();
+
+        let value = match self.kind {
+            StatKind::Int => match input.parse::<i32>() {
+                Ok(v) => StatValue::Int(v),
+                Err(_) => bail!("{} expects a whole number", self.name),
+            },
+            StatKind::Float => match input.parse::<f32>() {
+                Ok(v) if v.is_finite() => StatValue::Float(v),
+                _ => bail!("{} expects a number", self.name),
+            },
+            StatKind::AvgRate => bail!("{} is an average rate and can't be edited", self.name),
+        };
+
+        if let Some(min) = self.min
+            && value.as_f64() < min
+        {
+            bail!("{} must be at least {}", self.name, min);
+        }
+
+        if let Some(max) = self.max
+            && value.as_f64() > max
+        {
+            bail!("{} must be at most {}", self.name, max);
+        }
+
+        if self.increment_only && value.as_f64() < self.value.as_f64() {
+            bail!("{} can only increase from {}", self.name, self.value);
+        }
+
+        Ok(value)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AchievementData {
+    pub achievements: Vec<AchievementInfo>,
+}
+
+/// Achievements to unlock and to clear to reach some wanted state.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Changes {
+    pub to_set: Vec<String>,
+    pub to_clear: Vec<String>,
+}
+
+impl Changes {
+    /// What to change in `live` so each achievement is unlocked as `wanted`
+    /// says, where `None` leaves it alone.
+    pub fn diff(live: &[AchievementInfo], wanted: impl Fn(&str) -> Option<bool>) -> Self {
+        let mut changes = Self::default();
+
+        for achievement in live {
+            match wanted(&achievement.name) {
+                Some(true) if !achievement.unlocked => {
+                    changes.to_set.push(achievement.name.clone())
+                }
+                Some(false) if achievement.unlocked => {
+                    changes.to_clear.push(achievement.name.clone())
+                }
+                _ => {}
+            }
+        }
+
+        changes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.to_set.is_empty() && self.to_clear.is_empty()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ProcessResult {
+    pub name: String,
+    pub success: bool,
+}
+
+/// Sets or clears each achievement, then stores them all at once. Returns
+/// how each one went alongside whether the store succeeded, since a failed
+/// store doesn't make the individual results any less worth reporting.
+pub fn process_achievements(
+    backend: &mut dyn StatsBackend,
+    id: u32,
+    achievement_names: Vec<String>,
+    clear: bool,
+) -> (Vec<ProcessResult>, Result<()>) {
+    let results: Vec<ProcessResult> = achievement_names
+        .iter()
+        .map(|name| {
+            let success = if clear {
+                backend.clear_achievement(id, name)
+            } else {
+                backend.set_achievement(id, name)
+            }
+            .is_ok();
+
+            ProcessResult {
+                name: name.clone(),
+                success,
+            }
+        })
+        .collect();
+
+    (results, backend.store_stats(id))
+}
+
+/// Like `process_achievements`, for stat values.
+pub fn process_stats(
+    backend: &mut dyn StatsBackend,
+    id: u32,
+    changes: Vec<(String, StatValue)>,
+) -> (Vec<ProcessResult>, Result<()>) {
+    let results: Vec<ProcessResult> = changes
+        .into_iter()
+        .map(|(name, value)| {
+            let success = backend.set_stat(id, &name, value).is_ok();
+            ProcessResult { name, success }
+        })
+        .collect();
+
+    (results, backend.store_stats(id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stat_values_follow_their_kind() {
+        let stat: StatInfo =
+            serde_json::from_str(r#"{ "name": "Distance", "kind": "float", "value": 3 }"#).unwrap();
+        assert_eq!(stat.value, StatValue::Float(3.0));
+
+        let stat: StatInfo =
+            serde_json::from_str(r#"{ "name": "NumGames", "kind": "int", "value": 3 }"#).unwrap();
+        assert_eq!(stat.value, StatValue::Int(3));
+
+        let error = serde_json::from_str::<StatInfo>(
+            r#"{ "name": "NumGames", "kind": "int", "value": 2.5 }"#,
+        )
+        .unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .starts_with("NumGames is an int stat but has value 2.5")
+        );
+    }
+}
@@ -0,0 +1,90 @@
+use super::schema::{FALLBACK_LANGUAGE, load_schema};
+use super::{AchievementData, AchievementInfo, StatInfo, StatKind, StatValue, StatsBackend};
+use anyhow::{Result, bail};
+use std::collections::HashMap;
+
+/// Read-only backend that serves an app's achievements and stats straight from
+/// Steam's cached schema, without initialising the app through Steamworks.
+///
+/// The schema carries definitions only, so every achievement is reported as
+/// locked, with no global percentage, and every stat at its default value.
+#[derive(Default)]
+pub struct OfflineBackend;
+
+impl OfflineBackend {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl StatsBackend for OfflineBackend {
+    fn get_achievements(&mut self, id: u32) -> Result<AchievementData> {
+        let achievements = load_schema(id)?
+            .achievements
+            .into_iter()
+            .map(|definition| AchievementInfo {
+                display_name: definition.display_name(FALLBACK_LANGUAGE).to_string(),
+                description: definition.description(FALLBACK_LANGUAGE).to_string(),
+                hidden: definition.hidden,
+                name: definition.name,
+                unlocked: false,
+                unlocked_at: None,
+                percentage: None,
+                icon: definition.icon,
+                icon_gray: definition.icon_gray,
+            })
+            .collect();
+
+        Ok(AchievementData { achievements })
+    }
+
+    fn set_achievement(&mut self, _id: u32, name: &str) -> Result<()> {
+        bail!("Can't unlock {} while offline", name)
+    }
+
+    fn clear_achievement(&mut self, _id: u32, name: &str) -> Result<()> {
+        bail!("Can't clear {} while offline", name)
+    }
+
+    fn global_percentages(&mut self, id: u32) -> Result<Option<HashMap<String, f32>>> {
+        bail!("Global percentages for app {} need Steam", id)
+    }
+
+    fn get_stats(&mut self, id: u32) -> Result<Vec<StatInfo>> {
+        let stats = load_schema(id)?
+            .stats
+            .into_iter()
+            .map(|definition| {
+                let default = definition.default.unwrap_or_default();
+                let value = match definition.kind {
+                    StatKind::Int => StatValue::Int(default as i32),
+                    StatKind::Float | StatKind::AvgRate => StatValue::Float(default as f32),
+                };
+
+                StatInfo {
+                    name: definition.name,
+                    display_name: definition.display_name,
+                    kind: definition.kind,
+                    value,
+                    min: definition.min,
+                    max: definition.max,
+                    increment_only: definition.increment_only,
+                }
+            })
+            .collect();
+
+        Ok(stats)
+    }
+
+    fn set_stat(&mut self, _id: u32, name: &str, _value: StatValue) -> Result<()> {
+        bail!("Can't set {} while offline", name)
+    }
+
+    fn store_stats(&mut self, _id: u32) -> Result<()> {
+        bail!("Offline mode is read-only")
+    }
+
+    fn reset_all_stats(&mut self, _id: u32, _include_achievements: bool) -> Result<()> {
+        bail!("Offline mode is read-only")
+    }
+}
@@ -0,0 +1,79 @@
+use super::{StatValue, StatsBackend};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::{BufRead, Write};
+
+/// A call on the worker's backend, sent as one JSON object per line.
+///
+/// The app ID is fixed for the lifetime of a worker, so requests don't carry it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "request", rename_all = "snake_case")]
+pub enum Request {
+    GetAchievements,
+    SetAchievement { name: String },
+    ClearAchievement { name: String },
+    GlobalPercentages,
+    GetStats,
+    SetStat { name: String, value: StatValue },
+    StoreStats,
+    ResetAllStats { include_achievements: bool },
+}
+
+/// The worker's answer to a request: `{"ok": ...}` or `{"error": "..."}`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Response {
+    Ok(Value),
+    Error(String),
+}
+
+/// Answers requests from `input` against `backend` until `input` is closed.
+pub fn serve(
+    backend: &mut dyn StatsBackend,
+    id: u32,
+    input: impl BufRead,
+    mut output: impl Write,
+) -> Result<()> {
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match serde_json::from_str(&line) {
+            Ok(request) => respond(backend, id, request),
+            Err(e) => Response::Error(format!("Invalid request: {}", e)),
+        };
+
+        serde_json::to_writer(&mut output, &response)?;
+        output.write_all(b"\n")?;
+        output.flush()?;
+    }
+
+    Ok(())
+}
+
+fn respond(backend: &mut dyn StatsBackend, id: u32, request: Request) -> Response {
+    let result = match request {
+        Request::GetAchievements => reply(backend.get_achievements(id)),
+        Request::SetAchievement { name } => reply(backend.set_achievement(id, &name)),
+        Request::ClearAchievement { name } => reply(backend.clear_achievement(id, &name)),
+        Request::GlobalPercentages => reply(backend.global_percentages(id)),
+        Request::GetStats => reply(backend.get_stats(id)),
+        Request::SetStat { name, value } => reply(backend.set_stat(id, &name, value)),
+        Request::StoreStats => reply(backend.store_stats(id)),
+        Request::ResetAllStats {
+            include_achievements,
+        } => reply(backend.reset_all_stats(id, include_achievements)),
+    };
+
+    match result {
+        Ok(value) => Response::Ok(value),
+        Err(e) => Response::Error(e.to_string()),
+    }
+}
+
+fn reply<T: Serialize>(result: Result<T>) -> Result<Value> {
+    Ok(serde_json::to_value(result?)?)
+}
@@ -0,0 +1,365 @@
+use super::protocol::{Request, Response};
+use super::{AchievementData, StatInfo, StatValue, StatsBackend};
+use anyhow::{Context, Result, anyhow, bail};
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::env;
+use std::ffi::OsString;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+/// How long a worker gets to answer one request before it's assumed hung and
+/// killed. Steam calls that can legitimately take a while wait with their own,
+/// shorter deadlines inside the worker.
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Backend that runs each app in its own `sam worker` subprocess.
+///
+/// Steamworks binds a process to a single app ID for its whole lifetime, so
+/// switching games means starting a fresh worker rather than re-initialising
+/// the API in place. Requests and responses are line-delimited JSON over the
+/// worker's stdin and stdout, which the worker keeps clear of anything else.
+pub struct RemoteBackend {
+    worker_args: Vec<OsString>,
+    connection: Option<(u32, Connection)>,
+}
+
+impl RemoteBackend {
+    /// `worker_args` are passed on to every worker, e.g. `--fixture FILE`.
+    pub fn new(worker_args: Vec<OsString>) -> Self {
+        Self {
+            worker_args,
+            connection: None,
+        }
+    }
+
+    fn call<T: DeserializeOwned>(&mut self, id: u32, request: Request) -> Result<T> {
+        let connection = match self.connection.take() {
+            Some((current, connection)) if current == id && !connection.closed => connection,
+            _ => {
+                let exe = env::current_exe().context("Failed to locate the sam executable")?;
+                let mut command = Command::new(exe);
+                command
+                    .arg("worker")
+                    .arg("--id")
+                    .arg(id.to_string())
+                    .args(&self.worker_args);
+                Connection::spawn(&mut command)
+                    .with_context(|| format!("Failed to start worker for app {}", id))?
+            }
+        };
+
+        self.connection.insert((id, connection)).1.call(&request)
+    }
+}
+
+impl StatsBackend for RemoteBackend {
+    fn get_achievements(&mut self, id: u32) -> Result<AchievementData> {
+        self.call(id, Request::GetAchievements)
+    }
+
+    fn set_achievement(&mut self, id: u32, name: &str) -> Result<()> {
+        let name = name.to_string();
+        self.call(id, Request::SetAchievement { name })
+    }
+
+    fn clear_achievement(&mut self, id: u32, name: &str) -> Result<()> {
+        let name = name.to_string();
+        self.call(id, Request::ClearAchievement { name })
+    }
+
+    fn global_percentages(&mut self, id: u32) -> Result<Option<HashMap<String, f32>>> {
+        self.call(id, Request::GlobalPercentages)
+    }
+
+    fn get_stats(&mut self, id: u32) -> Result<Vec<StatInfo>> {
+        self.call(id, Request::GetStats)
+    }
+
+    fn set_stat(&mut self, id: u32, name: &str, value: StatValue) -> Result<()> {
+        let name = name.to_string();
+        self.call(id, Request::SetStat { name, value })
+    }
+
+    fn store_stats(&mut self, id: u32) -> Result<()> {
+        self.call(id, Request::StoreStats)
+    }
+
+    fn reset_all_stats(&mut self, id: u32, include_achievements: bool) -> Result<()> {
+        self.call(
+            id,
+            Request::ResetAllStats {
+                include_achievements,
+            },
+        )
+    }
+}
+
+/// The client end of a worker's stdio. Responses are read on a helper thread
+/// so a worker that stops answering can't block the caller forever.
+struct Connection {
+    child: Option<Child>,
+    input: Box<dyn Write + Send>,
+    responses: Receiver<io::Result<String>>,
+    timeout: Duration,
+    closed: bool,
+}
+
+impl Connection {
+    fn spawn(command: &mut Command) -> Result<Self> {
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+
+        let input = child.stdin.take().context("Worker has no stdin")?;
+        let output = child.stdout.take().context("Worker has no stdout")?;
+
+        let mut connection = Self::new(input, output);
+        connection.child = Some(child);
+        Ok(connection)
+    }
+
+    fn new(input: impl Write + Send + 'static, output: impl Read + Send + 'static) -> Self {
+        let (sender, responses) = mpsc::channel();
+        thread::spawn(move || {
+            let mut output = BufReader::new(output);
+            loop {
+                let mut line = String::new();
+                match output.read_line(&mut line) {
+                    // NOTE: Dropping the sender at EOF tells the caller the
+                    // worker exited
+                    Ok(0) => break,
+                    Ok(_) => {
+                        if sender.send(Ok(line)).is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) => {
+                        let _ = sender.send(Err(e));
+                        break;
+                    }
+                }
+            }
+        });
+
+        Self {
+            child: None,
+            input: Box::new(input),
+            responses,
+            timeout: RESPONSE_TIMEOUT,
+            closed: false,
+        }
+    }
+
+    fn call<T: DeserializeOwned>(&mut self, request: &Request) -> Result<T> {
+        match self.exchange(request)? {
+            Response::Ok(value) => Ok(serde_json::from_value(value)?),
+            Response::Error(e) => Err(anyhow!(e)),
+        }
+    }
+
+    fn exchange(&mut self, request: &Request) -> Result<Response> {
+        let response = self.try_exchange(request);
+        if response.is_err() {
+            // NOTE: Requests and responses can't be paired up after a failure,
+            // so the next call starts a fresh worker
+            self.close();
+        }
+        response
+    }
+
+    fn try_exchange(&mut self, request: &Request) -> Result<Response> {
+        let mut line = serde_json::to_string(request)?;
+        line.push('\n');
+
+        if self
+            .input
+            .write_all(line.as_bytes())
+            .and_then(|()| self.input.flush())
+            .is_err()
+        {
+            bail!("Worker exited unexpectedly");
+        }
+
+        let line = match self.responses.recv_timeout(self.timeout) {
+            Ok(line) => line.context("Failed to read from worker")?,
+            Err(RecvTimeoutError::Timeout) => {
+                bail!("Worker did not respond within {:?}", self.timeout)
+            }
+            Err(RecvTimeoutError::Disconnected) => bail!("Worker exited unexpectedly"),
+        };
+
+        serde_json::from_str(&line)
+            .map_err(|e| anyhow!("Invalid response from worker: {} ({})", line.trim_end(), e))
+    }
+
+    /// Marks the connection unusable and kills the worker, which also ends the
+    /// reader thread once the worker's stdout closes.
+    fn close(&mut self) {
+        self.closed = true;
+        if let Some(child) = self.child.as_mut() {
+            let _ = child.kill();
+        }
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        if let Some(child) = self.child.as_mut() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::steam::MockBackend;
+    use crate::steam::protocol::serve;
+    use std::io::{self, PipeReader, PipeWriter};
+    use std::thread::{self, JoinHandle};
+
+    const FIXTURE: &str = r#"{ "apps": { "480": {
+        "achievements": [
+            { "name": "ACH_WIN_ONE_GAME", "unlocked": false, "percentage": 42.0 },
+            { "name": "ACH_TRAVEL_FAR_ACCUM", "unlocked": true, "percentage": null }
+        ],
+        "stats": [{ "name": "NumGames", "kind": "int", "value": 3 }],
+        "failing": ["ACH_TRAVEL_FAR_ACCUM"]
+    } } }"#;
+
+    /// Starts a fake worker on a thread, running `worker` over a pair of pipes.
+    fn fake_worker(
+        worker: impl FnOnce(BufReader<PipeReader>, PipeWriter) + Send + 'static,
+    ) -> (Connection, JoinHandle<()>) {
+        let (request_reader, request_writer) = io::pipe().unwrap();
+        let (response_reader, response_writer) = io::pipe().unwrap();
+
+        let handle = thread::spawn(move || worker(BufReader::new(request_reader), response_writer));
+        (Connection::new(request_writer, response_reader), handle)
+    }
+
+    fn mock_worker(id: u32) -> (Connection, JoinHandle<()>) {
+        fake_worker(move |input, output| {
+            let mut backend: MockBackend = serde_json::from_str(FIXTURE).unwrap();
+            serve(&mut backend, id, input, output).unwrap();
+        })
+    }
+
+    #[test]
+    fn round_trips_achievements() {
+        let (mut connection, _) = mock_worker(480);
+
+        let data: AchievementData = connection.call(&Request::GetAchievements).unwrap();
+        assert_eq!(data.achievements.len(), 2);
+        assert_eq!(data.achievements[0].percentage, Some(42.0));
+        assert_eq!(data.achievements[1].percentage, None);
+
+        let name = "ACH_WIN_ONE_GAME".to_string();
+        let () = connection.call(&Request::SetAchievement { name }).unwrap();
+        let () = connection.call(&Request::StoreStats).unwrap();
+
+        let data: AchievementData = connection.call(&Request::GetAchievements).unwrap();
+        assert!(data.achievements[0].unlocked);
+        assert!(data.achievements[0].unlocked_at.is_some());
+    }
+
+    #[test]
+    fn round_trips_stats() {
+        let (mut connection, _) = mock_worker(480);
+
+        let name = "NumGames".to_string();
+        let value = StatValue::Int(7);
+        let () = connection.call(&Request::SetStat { name, value }).unwrap();
+        let () = connection.call(&Request::StoreStats).unwrap();
+
+        let stats: Vec<StatInfo> = connection.call(&Request::GetStats).unwrap();
+        assert_eq!(stats[0].value, StatValue::Int(7));
+    }
+
+    #[test]
+    fn forwards_backend_errors() {
+        let (mut connection, _) = mock_worker(480);
+
+        let name = "ACH_TRAVEL_FAR_ACCUM".to_string();
+        let error = connection
+            .call::<()>(&Request::ClearAchievement { name })
+            .unwrap_err();
+        assert_eq!(error.to_string(), "Failed to update ACH_TRAVEL_FAR_ACCUM");
+        assert!(!connection.closed);
+
+        let (mut connection, _) = mock_worker(620);
+        let error = connection
+            .call::<AchievementData>(&Request::GetAchievements)
+            .unwrap_err();
+        assert_eq!(error.to_string(), "App 620 not in your library");
+    }
+
+    #[test]
+    fn rejects_malformed_requests() {
+        let (mut connection, _) = fake_worker(|input, output| {
+            let mut backend = MockBackend::default();
+            serve(&mut backend, 480, input, output).unwrap();
+        });
+
+        connection
+            .input
+            .write_all(b"{\"request\":\"fly\"}\n")
+            .unwrap();
+        let line = connection.responses.recv().unwrap().unwrap();
+
+        let response: Response = serde_json::from_str(&line).unwrap();
+        assert!(matches!(response, Response::Error(e) if e.starts_with("Invalid request")));
+    }
+
+    #[test]
+    fn rejects_invalid_responses() {
+        let (mut connection, _) = fake_worker(|mut input, mut output| {
+            let mut line = String::new();
+            input.read_line(&mut line).unwrap();
+            output
+                .write_all(b"Setting breakpad minidump AppID = 480\n")
+                .unwrap();
+        });
+
+        let error = connection.call::<()>(&Request::StoreStats).unwrap_err();
+        assert!(
+            error.to_string().starts_with(
+                "Invalid response from worker: Setting breakpad minidump AppID = 480 ("
+            )
+        );
+        assert!(connection.closed);
+    }
+
+    #[test]
+    fn reports_a_worker_that_exits() {
+        let (mut connection, handle) = fake_worker(|_, _| {});
+        handle.join().unwrap();
+
+        let error = connection.call::<()>(&Request::StoreStats).unwrap_err();
+        assert_eq!(error.to_string(), "Worker exited unexpectedly");
+        assert!(connection.closed);
+    }
+
+    #[test]
+    fn gives_up_on_a_worker_that_hangs() {
+        let (mut connection, _) = fake_worker(|mut input, output| {
+            let mut line = String::new();
+            input.read_line(&mut line).unwrap();
+            // Keep stdout open without ever answering
+            thread::sleep(Duration::from_secs(60));
+            drop(output);
+        });
+        connection.timeout = Duration::from_millis(50);
+
+        let error = connection.call::<()>(&Request::StoreStats).unwrap_err();
+        assert_eq!(error.to_string(), "Worker did not respond within 50ms");
+        assert!(connection.closed);
+    }
+}
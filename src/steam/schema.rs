@@ -0,0 +1,281 @@
+use super::StatKind;
+use crate::kv::{self, Value};
+use crate::library::steam_root;
+use anyhow::{Context, Result, bail};
+use std::collections::BTreeMap;
+use std::fs;
+
+const STAT_TYPE_INT: i64 = 1;
+const STAT_TYPE_FLOAT: i64 = 2;
+const STAT_TYPE_AVGRATE: i64 = 3;
+const STAT_TYPE_ACHIEVEMENTS: i64 = 4;
+const STAT_TYPE_GROUP_ACHIEVEMENTS: i64 = 5;
+
+/// Language used when a string isn't translated into the requested one.
+pub const FALLBACK_LANGUAGE: &str = "english";
+
+/// The contents of a `UserGameStatsSchema_<appid>.bin` file.
+#[derive(Clone, Debug, Default)]
+pub struct Schema {
+    pub achievements: Vec<AchievementDefinition>,
+    pub stats: Vec<StatDefinition>,
+}
+
+impl Schema {
+    pub fn achievement(&self, name: &str) -> Option<&AchievementDefinition> {
+        self.achievements.iter().find(|a| a.name == name)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct AchievementDefinition {
+    pub name: String,
+    /// Display names keyed by Steam language name, e.g. `english`.
+    pub display_names: BTreeMap<String, String>,
+    /// Descriptions keyed by Steam language name.
+    pub descriptions: BTreeMap<String, String>,
+    pub hidden: bool,
+    /// File name of the unlocked icon on Steam's CDN, e.g. `<sha1>.jpg`.
+    pub icon: Option<String>,
+    /// File name of the locked icon on Steam's CDN.
+    pub icon_gray: Option<String>,
+}
+
+impl AchievementDefinition {
+    pub fn display_name(&self, language: &str) -> &str {
+        localized(&self.display_names, language)
+    }
+
+    pub fn description(&self, language: &str) -> &str {
+        localized(&self.descriptions, language)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct StatDefinition {
+    pub name: String,
+    pub display_name: String,
+    pub kind: StatKind,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub default: Option<f64>,
+    pub increment_only: bool,
+}
+
+/// Reads Steam's cached schema for `id`.
+///
+/// Steam writes this file the first time the app's stats are requested, so it
+/// is available without initialising the app through Steamworks.
+pub fn load_schema(id: u32) -> Result<Schema> {
+    let root = steam_root().context("Steam installation not found")?;
+    let path = root
+        .join("appcache/stats")
+        .join(format!("UserGameStatsSchema_{}.bin", id));
+
+    let data = fs::read(&path).with_context(|| format!("No cached stats schema for app {}", id))?;
+    parse_schema(&data, id).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+pub fn parse_schema(data: &[u8], id: u32) -> Result<Schema> {
+    let root = kv::parse_binary(data)?;
+    let Some(app) = root.get(&id.to_string()) else {
+        bail!("Schema has no entry for app {}", id);
+    };
+
+    let mut schema = Schema::default();
+
+    let stats = app.get("stats").map(Value::entries).unwrap_or_default();
+    for (_, stat) in stats {
+        match stat.get("type").and_then(Value::as_i64) {
+            Some(STAT_TYPE_ACHIEVEMENTS | STAT_TYPE_GROUP_ACHIEVEMENTS) => {
+                let bits = stat.get("bits").map(Value::entries).unwrap_or_default();
+                schema.achievements.extend(
+                    bits.iter()
+                        .filter_map(|(_, bit)| achievement_definition(bit)),
+                );
+            }
+            Some(kind) => schema.stats.extend(stat_definition(stat, kind)),
+            None => {}
+        }
+    }
+
+    Ok(schema)
+}
+
+fn achievement_definition(bit: &Value) -> Option<AchievementDefinition> {
+    let name = bit.get("name")?.as_str()?.to_string();
+    let display = bit.get("display");
+    let attribute = |key: &str| display.and_then(|d| d.get(key));
+
+    Some(AchievementDefinition {
+        name,
+        display_names: translations(attribute("name")),
+        descriptions: translations(attribute("desc")),
+        hidden: attribute("hidden")
+            .and_then(Value::as_i64)
+            .is_some_and(|v| v != 0),
+        icon: attribute("icon").and_then(icon_name),
+        icon_gray: attribute("icon_gray").and_then(icon_name),
+    })
+}
+
+fn stat_definition(stat: &Value, kind: i64) -> Option<StatDefinition> {
+    let kind = match kind {
+        STAT_TYPE_INT => StatKind::Int,
+        STAT_TYPE_FLOAT => StatKind::Float,
+        STAT_TYPE_AVGRATE => StatKind::AvgRate,
+        _ => return None,
+    };
+
+    let name = stat.get("name")?.as_str()?.to_string();
+    let display_name = stat
+        .get("display")
+        .and_then(|display| display.get("name"))
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+
+    Some(StatDefinition {
+        name,
+        display_name,
+        kind,
+        min: stat.get("min").and_then(Value::as_f64),
+        max: stat.get("max").and_then(Value::as_f64),
+        default: stat.get("default").and_then(Value::as_f64),
+        increment_only: stat
+            .get("incrementonly")
+            .and_then(Value::as_i64)
+            .is_some_and(|v| v != 0),
+    })
+}
+
+/// Localized strings are either a plain string, taken to be English, or a
+/// section keyed by language alongside a `token` entry naming the string.
+fn translations(value: Option<&Value>) -> BTreeMap<String, String> {
+    match value {
+        Some(Value::String(s)) => BTreeMap::from([(FALLBACK_LANGUAGE.to_string(), s.clone())]),
+        Some(section) => section
+            .entries()
+            .iter()
+            .filter(|(language, _)| !language.eq_ignore_ascii_case("token"))
+            .filter_map(|(language, text)| {
+                Some((language.to_ascii_lowercase(), text.as_str()?.to_string()))
+            })
+            .collect(),
+        None => BTreeMap::new(),
+    }
+}
+
+fn localized<'a>(translations: &'a BTreeMap<String, String>, language: &str) -> &'a str {
+    translations
+        .get(language)
+        .or_else(|| translations.get(FALLBACK_LANGUAGE))
+        .or_else(|| translations.values().next())
+        .map(String::as_str)
+        .unwrap_or_default()
+}
+
+fn icon_name(value: &Value) -> Option<String> {
+    value
+        .as_str()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPACEWAR: &[u8] = include_bytes!("../../tests/fixtures/UserGameStatsSchema_480.bin");
+    const PORTAL_2: &[u8] = include_bytes!("../../tests/fixtures/UserGameStatsSchema_620.bin");
+
+    #[test]
+    fn parses_achievements_in_bit_order() {
+        let schema = parse_schema(SPACEWAR, 480).unwrap();
+        let names: Vec<_> = schema
+            .achievements
+            .iter()
+            .map(|a| a.name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "ACH_WIN_ONE_GAME",
+                "ACH_WIN_100_GAMES",
+                "ACH_TRAVEL_FAR_ACCUM"
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_translations_without_tokens() {
+        let schema = parse_schema(SPACEWAR, 480).unwrap();
+        let winner = schema.achievement("ACH_WIN_ONE_GAME").unwrap();
+
+        assert_eq!(winner.display_names.len(), 2);
+        assert_eq!(winner.display_name("english"), "Winner");
+        assert_eq!(winner.display_name("german"), "Gewinner");
+        assert_eq!(winner.description("german"), "Gewinne ein Spiel.");
+        assert_eq!(winner.display_name("french"), "Winner");
+    }
+
+    #[test]
+    fn parses_hidden_flags_and_icons() {
+        let schema = parse_schema(SPACEWAR, 480).unwrap();
+        let winner = schema.achievement("ACH_WIN_ONE_GAME").unwrap();
+        let traveler = schema.achievement("ACH_TRAVEL_FAR_ACCUM").unwrap();
+
+        assert!(!winner.hidden);
+        assert!(traveler.hidden);
+        assert_eq!(winner.icon.as_deref(), Some("3e6a3f5c.jpg"));
+        assert_eq!(winner.icon_gray.as_deref(), Some("9d1c2b7a.jpg"));
+    }
+
+    #[test]
+    fn parses_stat_definitions() {
+        let schema = parse_schema(SPACEWAR, 480).unwrap();
+        let kinds: Vec<_> = schema.stats.iter().map(|s| s.kind).collect();
+        assert_eq!(kinds, [StatKind::Int, StatKind::Float, StatKind::AvgRate]);
+
+        let games = &schema.stats[0];
+        assert_eq!(games.name, "NumGames");
+        assert_eq!(games.display_name, "Games Played");
+        assert_eq!(games.min, Some(0.0));
+        assert_eq!(games.max, Some(1000.0));
+        assert_eq!(games.default, Some(0.0));
+        assert!(games.increment_only);
+        assert!(!schema.stats[1].increment_only);
+        assert_eq!(schema.stats[1].max, None);
+    }
+
+    #[test]
+    fn parses_numeric_values_and_plain_strings() {
+        let schema = parse_schema(PORTAL_2, 620).unwrap();
+
+        let ride = schema.achievement("ACH_SURVIVE_CONTAINER_RIDE").unwrap();
+        assert_eq!(ride.display_name("german"), "Wake Up Call");
+        assert_eq!(ride.description("english"), "Survive the manual override.");
+        assert!(ride.hidden);
+
+        let bare = schema.achievement("ACH_NO_DISPLAY").unwrap();
+        assert_eq!(bare.display_name("english"), "");
+        assert!(!bare.hidden);
+        assert_eq!(bare.icon, None);
+
+        assert_eq!(schema.stats[0].max, Some(2147483647.0));
+        assert_eq!(schema.stats[1].kind, StatKind::Float);
+        assert_eq!(schema.stats[1].max, Some(1000.5));
+    }
+
+    #[test]
+    fn rejects_schema_for_another_app() {
+        let error = parse_schema(SPACEWAR, 620).unwrap_err();
+        assert_eq!(error.to_string(), "Schema has no entry for app 620");
+    }
+
+    #[test]
+    fn rejects_truncated_schema() {
+        assert!(parse_schema(&SPACEWAR[..SPACEWAR.len() / 2], 480).is_err());
+    }
+}
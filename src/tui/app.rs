@@ -1,33 +1,74 @@
-use super::config::AppConfig;
-use super::models::{AchievementItem, AchievementStatus, SortColumn, SortOrder, Status};
+use super::config::ConfigFile;
+use super::history::{Edit, History};
+use super::icon::{Icon, IconCache};
+use super::models::{
+    AchievementItem, AchievementStatus, ResetPrompt, Review, ReviewEntry, RowFilter, SortColumn,
+    SortOrder, Status, Tab, Visibility,
+};
 use super::search::fuzzy_score;
-use crate::steam::{AchievementData, process_achievements};
+use super::sort::sort;
+use super::stats::StatsView;
+use super::worker::Batch;
+use crate::snapshot::{Snapshot, SnapshotEntry};
+use crate::steam::{AchievementData, Changes};
+use crate::time;
+use ratatui::layout::Rect;
 use ratatui::widgets::TableState;
+use std::cmp::Reverse;
+use std::collections::HashMap;
 
 pub struct App {
     pub achievements: Vec<AchievementItem>,
     pub current_index: usize,
     pub app_id: u32,
     pub table_state: TableState,
+    /// Where the table was last drawn, for mapping mouse clicks to rows.
+    pub table_area: Rect,
+    pub show_details: bool,
+    /// Where the details popup was last drawn, if it covers the table.
+    pub details_popup: Option<Rect>,
+    pub icons: IconCache,
     pub status: Option<Status>,
     pub sort_column: SortColumn,
     pub sort_order: SortOrder,
     pub search_query: String,
+    /// Positions in `view` that match the search, best first.
+    pub search_matches: Vec<usize>,
+    pub search_match: usize,
+    /// Rows must match this to be shown, ranked best match first.
+    pub filter_query: String,
+    pub row_filter: Option<RowFilter>,
+    pub view: Vec<usize>,
+    pub visibility: Visibility,
+    pub hide_spoilers: bool,
+    pub tab: Tab,
+    pub stats: StatsView,
+    pub reset_prompt: Option<ResetPrompt>,
+    pub review: Option<Review>,
+    pub history: History,
+    /// What the last apply wrote to Steam, so it can be reverted.
+    pub last_applied: Option<Changes>,
+    /// Where sort, spoiler and details settings are kept between runs.
+    pub config: ConfigFile,
 }
 
 impl App {
-    pub fn new(achievements: AchievementData, app_id: u32) -> Self {
-        let config: AppConfig = confy::load("sam", None).unwrap_or_default();
-
+    pub fn new(achievements: AchievementData, app_id: u32, config: ConfigFile) -> Self {
         let mut achievements: Vec<AchievementItem> = achievements
             .achievements
             .into_iter()
             .map(|info| AchievementItem {
                 name: info.name,
+                display_name: info.display_name,
+                description: info.description,
+                hidden: info.hidden,
                 selected: info.unlocked,
                 unlocked: info.unlocked,
+                unlocked_at: info.unlocked_at,
                 percentage: info.percentage,
                 status: AchievementStatus::Unchanged,
+                icon: info.icon,
+                icon_gray: info.icon_gray,
             })
             .collect();
 
@@ -44,103 +85,390 @@ impl App {
             current_index: 0,
             app_id,
             table_state,
+            table_area: Rect::default(),
+            show_details: config.config.show_details,
+            details_popup: None,
+            icons: IconCache::new(app_id),
             status: None,
-            sort_column: config.sort_column,
-            sort_order: config.sort_order,
+            sort_column: config.config.sort_column.clone(),
+            sort_order: config.config.sort_order.clone(),
             search_query: String::new(),
+            search_matches: Vec::new(),
+            search_match: 0,
+            filter_query: String::new(),
+            row_filter: None,
+            view: Vec::new(),
+            visibility: Visibility::All,
+            hide_spoilers: config.config.hide_spoilers,
+            tab: Tab::Achievements,
+            stats: StatsView::default(),
+            reset_prompt: None,
+            review: None,
+            history: History::default(),
+            last_applied: None,
+            config,
         };
 
         app.sort_achievements();
         app
     }
 
+    pub fn visible_achievements(&self) -> impl Iterator<Item = &AchievementItem> {
+        self.view.iter().map(|&index| &self.achievements[index])
+    }
+
+    /// Rebuilds the rows shown from the visibility, quick filter and filter
+    /// query, keeping the same achievement highlighted when it is still shown.
+    pub fn refresh_view(&mut self) {
+        let highlighted = self
+            .highlighted()
+            .map(|achievement| achievement.name.clone());
+        let query = self.filter_query.trim();
+
+        let mut scored: Vec<(usize, i64)> = self
+            .achievements
+            .iter()
+            .enumerate()
+            .filter(|(_, achievement)| self.visibility.matches(achievement))
+            .filter(|(_, achievement)| self.row_filter.is_none_or(|f| f.matches(achievement)))
+            .filter_map(|(index, achievement)| {
+                if query.is_empty() {
+                    Some((index, 0))
+                } else {
+                    match_score(achievement, query, self.hide_spoilers).map(|score| (index, score))
+                }
+            })
+            .collect();
+
+        // NOTE: Stable, so equally good matches keep the chosen sort order
+        scored.sort_by_key(|&(_, score)| Reverse(score));
+        self.view = scored.into_iter().map(|(index, _)| index).collect();
+
+        match highlighted.and_then(|name| self.position_of(&name)) {
+            Some(position) => self.current_index = position,
+            None => self.current_index = self.current_index.min(self.view.len().saturating_sub(1)),
+        }
+        self.table_state.select(Some(self.current_index));
+
+        if !self.search_query.trim().is_empty() {
+            self.rank_search_matches();
+        }
+    }
+
+    pub fn highlighted(&self) -> Option<&AchievementItem> {
+        self.view
+            .get(self.current_index)
+            .map(|&index| &self.achievements[index])
+    }
+
+    /// The icon of the highlighted achievement as it stands, locked or
+    /// unlocked, with its file name. Hidden achievements that are still
+    /// locked keep their icon to themselves while spoilers are hidden.
+    pub fn highlighted_icon(&mut self) -> Option<(String, Icon)> {
+        let achievement = self.highlighted()?;
+        if achievement.hidden && !achievement.unlocked && self.hide_spoilers {
+            return None;
+        }
+        let file = if achievement.unlocked {
+            achievement.icon.clone()
+        } else {
+            achievement.icon_gray.clone()
+        }?;
+        let icon = self.icons.get(&file)?.clone();
+        Some((file, icon))
+    }
+
+    /// Where an achievement sits in the current view, if it is shown.
+    fn position_of(&self, name: &str) -> Option<usize> {
+        self.view
+            .iter()
+            .position(|&index| self.achievements[index].name == name)
+    }
+
+    pub fn is_filtered(&self) -> bool {
+        !self.filter_query.trim().is_empty() || self.row_filter.is_some()
+    }
+
+    /// Re-ranks the rows for a new filter query, best match at the top.
+    pub fn update_filter(&mut self) {
+        self.refresh_view();
+        self.jump_to_top();
+    }
+
+    pub fn clear_filter(&mut self) {
+        self.filter_query.clear();
+        self.row_filter = None;
+        self.refresh_view();
+    }
+
+    /// Turns a quick filter on, or off if it is already the active one.
+    pub fn toggle_row_filter(&mut self, filter: RowFilter) {
+        self.row_filter = (self.row_filter != Some(filter)).then_some(filter);
+        self.refresh_view();
+        self.status = Some(Status::info(match self.row_filter {
+            Some(filter) => format!("Showing: {} achievements", filter),
+            None => "Showing: All states".to_string(),
+        }));
+    }
+
+    pub fn switch_tab(&mut self) {
+        self.tab = match self.tab {
+            Tab::Achievements => Tab::Stats,
+            Tab::Stats => Tab::Achievements,
+        };
+    }
+
     pub fn toggle_selection(&mut self) {
-        if !self.achievements.is_empty() {
-            self.achievements[self.current_index].selected =
-                !self.achievements[self.current_index].selected;
+        if let Some(&index) = self.view.get(self.current_index) {
+            self.edit_selection("Toggle", |app| {
+                app.achievements[index].selected = !app.achievements[index].selected;
+            });
         }
     }
 
     pub fn select_all(&mut self) {
-        for achievement in &mut self.achievements {
-            achievement.selected = true;
-        }
+        self.edit_selection("Select all", |app| {
+            for &index in &app.view {
+                app.achievements[index].selected = true;
+            }
+        });
     }
 
     pub fn deselect_all(&mut self) {
-        for achievement in &mut self.achievements {
-            achievement.selected = false;
+        self.edit_selection("Deselect all", |app| {
+            for &index in &app.view {
+                app.achievements[index].selected = false;
+            }
+        });
+    }
+
+    /// Runs a change to the selection and records what it flipped for undo.
+    fn edit_selection(&mut self, label: &'static str, edit: impl FnOnce(&mut Self)) {
+        let before: Vec<bool> = self.achievements.iter().map(|a| a.selected).collect();
+        edit(self);
+
+        let names: Vec<String> = self
+            .achievements
+            .iter()
+            .zip(before)
+            .filter(|(achievement, selected)| achievement.selected != *selected)
+            .map(|(achievement, _)| achievement.name.clone())
+            .collect();
+
+        if !names.is_empty() {
+            self.history.record(Edit { label, names });
+        }
+    }
+
+    fn flip_selection(&mut self, names: &[String]) {
+        for achievement in self.achievements.iter_mut() {
+            if names.contains(&achievement.name) {
+                achievement.selected = !achievement.selected;
+            }
         }
     }
 
+    pub fn undo(&mut self) {
+        let Some(edit) = self.history.undo().cloned() else {
+            self.status = Some(Status::info("Nothing to undo".to_string()));
+            return;
+        };
+
+        self.flip_selection(&edit.names);
+        self.status = Some(Status::info(format!(
+            "Undid: {} ({} achievement(s))",
+            edit.label,
+            edit.names.len()
+        )));
+    }
+
+    pub fn redo(&mut self) {
+        let Some(edit) = self.history.redo().cloned() else {
+            self.status = Some(Status::info("Nothing to redo".to_string()));
+            return;
+        };
+
+        self.flip_selection(&edit.names);
+        self.status = Some(Status::info(format!(
+            "Redid: {} ({} achievement(s))",
+            edit.label,
+            edit.names.len()
+        )));
+    }
+
+    /// Selects exactly the inverse of the last apply and opens the review, so
+    /// confirming writes it back the way it was. Other pending edits are
+    /// dropped, but can be brought back with undo.
+    pub fn revert_last_apply(&mut self) {
+        let Some(applied) = self.last_applied.clone() else {
+            self.status = Some(Status::info("Nothing applied to revert".to_string()));
+            return;
+        };
+
+        self.edit_selection("Revert last apply", |app| {
+            for achievement in app.achievements.iter_mut() {
+                achievement.selected = if applied.to_set.contains(&achievement.name) {
+                    false
+                } else if applied.to_clear.contains(&achievement.name) {
+                    true
+                } else {
+                    achievement.unlocked
+                };
+            }
+        });
+
+        self.status = if self.open_review() {
+            None
+        } else {
+            Some(Status::info("Nothing applied to revert".to_string()))
+        };
+    }
+
+    pub fn cycle_visibility(&mut self) {
+        self.visibility = self.visibility.next();
+        self.refresh_view();
+        self.status = Some(Status::info(format!("Showing: {}", self.visibility)));
+    }
+
+    pub fn toggle_spoilers(&mut self) {
+        self.hide_spoilers = !self.hide_spoilers;
+        self.save_config();
+        // Concealed descriptions stop matching the filter and search
+        self.refresh_view();
+        self.status = Some(Status::info(if self.hide_spoilers {
+            "Hidden achievement descriptions concealed".to_string()
+        } else {
+            "Hidden achievement descriptions revealed".to_string()
+        }));
+    }
+
+    pub fn toggle_details(&mut self) {
+        self.show_details = !self.show_details;
+        self.save_config();
+    }
+
     pub fn next(&mut self) {
-        if !self.achievements.is_empty() {
-            self.current_index = (self.current_index + 1) % self.achievements.len();
+        if !self.view.is_empty() {
+            self.current_index = (self.current_index + 1) % self.view.len();
             self.table_state.select(Some(self.current_index));
         }
     }
 
     pub fn jump_to(&mut self, index: usize) {
-        if !self.achievements.is_empty() {
-            self.current_index = index.min(self.achievements.len() - 1);
+        if !self.view.is_empty() {
+            self.current_index = index.min(self.view.len() - 1);
             self.table_state.select(Some(self.current_index));
         }
     }
 
-    pub fn search_first_match(&mut self) -> bool {
-        if self.search_query.trim().is_empty() {
-            return false;
+    /// Ranks the shown rows against the search query and jumps to the best
+    /// match. Returns false if nothing matches.
+    pub fn update_search(&mut self) -> bool {
+        self.rank_search_matches();
+        self.search_match = 0;
+
+        match self.search_matches.first() {
+            Some(&position) => {
+                self.jump_to(position);
+                true
+            }
+            None => false,
         }
+    }
 
-        let query = self.search_query.to_lowercase();
+    fn rank_search_matches(&mut self) {
+        let query = self.search_query.trim();
 
-        let mut best: Option<(usize, i64)> = None;
-        for (index, achievement) in self.achievements.iter().enumerate() {
-            let name = achievement.name.to_lowercase();
-            if let Some(score) = fuzzy_score(&name, &query) {
-                match best {
-                    None => best = Some((index, score)),
-                    Some((_, best_score)) if score > best_score => best = Some((index, score)),
-                    _ => {}
-                }
-            }
+        let mut scored: Vec<(usize, i64)> = self
+            .visible_achievements()
+            .enumerate()
+            .filter_map(|(position, achievement)| {
+                match_score(achievement, query, self.hide_spoilers).map(|score| (position, score))
+            })
+            .collect();
+
+        scored.sort_by_key(|&(_, score)| Reverse(score));
+        self.search_matches = scored.into_iter().map(|(position, _)| position).collect();
+        self.search_match = self
+            .search_match
+            .min(self.search_matches.len().saturating_sub(1));
+    }
+
+    pub fn has_search_matches(&self) -> bool {
+        !self.search_matches.is_empty()
+    }
+
+    pub fn clear_search(&mut self) {
+        self.search_query.clear();
+        self.search_matches.clear();
+        self.search_match = 0;
+    }
+
+    /// Moves to the next best search match, wrapping back to the best.
+    pub fn next_match(&mut self) {
+        if !self.search_matches.is_empty() {
+            self.search_match = (self.search_match + 1) % self.search_matches.len();
+            self.show_search_match();
         }
+    }
 
-        if let Some((index, _)) = best {
-            self.jump_to(index);
-            return true;
+    pub fn previous_match(&mut self) {
+        if !self.search_matches.is_empty() {
+            self.search_match = self
+                .search_match
+                .checked_sub(1)
+                .unwrap_or(self.search_matches.len() - 1);
+            self.show_search_match();
         }
+    }
 
-        false
+    fn show_search_match(&mut self) {
+        self.jump_to(self.search_matches[self.search_match]);
+        self.status = Some(Status::info(format!(
+            "Match {}/{} for: {}",
+            self.search_match + 1,
+            self.search_matches.len(),
+            self.search_query.trim()
+        )));
+    }
+
+    /// The query whose matched characters are highlighted: the search while
+    /// there is one, otherwise the filter.
+    pub fn highlight_query(&self) -> &str {
+        if self.search_query.trim().is_empty() {
+            self.filter_query.trim()
+        } else {
+            self.search_query.trim()
+        }
     }
 
     pub fn previous(&mut self) {
-        if !self.achievements.is_empty() {
+        if !self.view.is_empty() {
             if self.current_index > 0 {
                 self.current_index -= 1;
             } else {
-                self.current_index = self.achievements.len() - 1;
+                self.current_index = self.view.len() - 1;
             }
             self.table_state.select(Some(self.current_index));
         }
     }
 
     pub fn jump_to_top(&mut self) {
-        if !self.achievements.is_empty() {
+        if !self.view.is_empty() {
             self.current_index = 0;
             self.table_state.select(Some(self.current_index));
         }
     }
 
     pub fn jump_to_bottom(&mut self) {
-        if !self.achievements.is_empty() {
-            self.current_index = self.achievements.len() - 1;
+        if !self.view.is_empty() {
+            self.current_index = self.view.len() - 1;
             self.table_state.select(Some(self.current_index));
         }
     }
 
     pub fn page_up(&mut self) {
-        if !self.achievements.is_empty() {
+        if !self.view.is_empty() {
             let page_size = 10;
             if self.current_index >= page_size {
                 self.current_index -= page_size;
@@ -152,9 +480,9 @@ impl App {
     }
 
     pub fn page_down(&mut self) {
-        if !self.achievements.is_empty() {
+        if !self.view.is_empty() {
             let page_size = 10;
-            let max_index = self.achievements.len() - 1;
+            let max_index = self.view.len() - 1;
             if self.current_index + page_size <= max_index {
                 self.current_index += page_size;
             } else {
@@ -164,26 +492,124 @@ impl App {
         }
     }
 
-    pub fn process_changes(&mut self) {
-        let to_set: Vec<String> = self
+    /// Fills in global percentages that arrived after the app was loaded,
+    /// keeping the same achievement highlighted through the re-sort.
+    pub fn update_percentages(&mut self, percentages: &HashMap<String, f32>) {
+        for achievement in self.achievements.iter_mut() {
+            if let Some(&percentage) = percentages.get(&achievement.name) {
+                achievement.percentage = Some(percentage);
+            }
+        }
+
+        self.sort_achievements();
+    }
+
+    /// Achievements to unlock and to clear so Steam matches the selection.
+    pub fn pending_changes(&self) -> Changes {
+        let to_set = self
             .achievements
             .iter()
             .filter(|a| a.selected && !a.unlocked)
             .map(|a| a.name.clone())
             .collect();
 
-        let to_clear: Vec<String> = self
+        let to_clear = self
             .achievements
             .iter()
             .filter(|a| !a.selected && a.unlocked)
             .map(|a| a.name.clone())
             .collect();
 
+        Changes { to_set, to_clear }
+    }
+
+    /// Lists the pending changes for confirmation. Returns false, without
+    /// opening anything, if there are none.
+    pub fn open_review(&mut self) -> bool {
+        let changes = self.pending_changes();
+        if changes.is_empty() {
+            return false;
+        }
+
+        let entries = changes
+            .to_set
+            .into_iter()
+            .map(|name| ReviewEntry { name, unlock: true })
+            .chain(changes.to_clear.into_iter().map(|name| ReviewEntry {
+                name,
+                unlock: false,
+            }))
+            .collect();
+
+        let mut review = Review {
+            entries,
+            ..Default::default()
+        };
+        review.table_state.select(Some(0));
+        self.review = Some(review);
+        true
+    }
+
+    /// Drops the highlighted review entry from the changes, or puts it back,
+    /// by flipping the achievement's selection.
+    pub fn toggle_review_entry(&mut self) {
+        let Some(name) = self
+            .review
+            .as_ref()
+            .and_then(|review| review.current())
+            .map(|entry| entry.name.clone())
+        else {
+            return;
+        };
+
+        self.edit_selection("Review edit", |app| {
+            if let Some(achievement) = app.achievements.iter_mut().find(|a| a.name == name) {
+                achievement.selected = !achievement.selected;
+            }
+        });
+    }
+
+    /// Whether applying now would change this achievement.
+    pub fn is_pending(&self, name: &str) -> bool {
+        self.achievements
+            .iter()
+            .any(|a| a.name == name && a.selected != a.unlocked)
+    }
+
+    /// The unlocked set as Steam last reported it, ignoring the selection.
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot::new(
+            self.app_id,
+            self.achievements
+                .iter()
+                .map(|a| SnapshotEntry {
+                    name: a.name.clone(),
+                    unlocked: a.unlocked,
+                    unlocked_at: a.unlocked_at,
+                })
+                .collect(),
+        )
+    }
+
+    /// Selects exactly what was unlocked in `snapshot`, so applying restores it.
+    pub fn select_snapshot(&mut self, snapshot: &Snapshot) {
+        let wanted = snapshot.unlocked();
+        self.edit_selection("Load snapshot", |app| {
+            for achievement in app.achievements.iter_mut() {
+                if let Some(&unlocked) = wanted.get(achievement.name.as_str()) {
+                    achievement.selected = unlocked;
+                }
+            }
+        });
+    }
+
+    pub fn apply_results(&mut self, unlocked: Batch, cleared: Batch) {
         let mut success_count = 0;
         let mut fail_count = 0;
+        let mut applied = Changes::default();
 
-        if !to_set.is_empty() {
-            match process_achievements(self.app_id, to_set.clone(), false) {
+        for (batch, unlock) in [(unlocked, true), (cleared, false)] {
+            match batch.results {
                 Ok(results) => {
                     for result in results {
                         if let Some(achievement) =
@@ -191,8 +617,15 @@ impl App {
                         {
                             if result.success {
                                 achievement.status = AchievementStatus::Success;
-                                achievement.unlocked = true;
+                                achievement.unlocked = unlock;
+                                achievement.unlocked_at = unlock.then(time::now);
                                 success_count += 1;
+
+                                if unlock {
+                                    applied.to_set.push(result.name);
+                                } else {
+                                    applied.to_clear.push(result.name);
+                                }
                             } else {
                                 achievement.status = AchievementStatus::Failed;
                                 fail_count += 1;
@@ -201,8 +634,8 @@ impl App {
                     }
                 }
                 Err(e) => {
-                    self.status = Some(Status::error(e.to_string()));
-                    for name in to_set {
+                    self.status = Some(Status::error(e));
+                    for name in batch.names {
                         if let Some(achievement) =
                             self.achievements.iter_mut().find(|a| a.name == name)
                         {
@@ -214,36 +647,8 @@ impl App {
             }
         }
 
-        if !to_clear.is_empty() {
-            match process_achievements(self.app_id, to_clear.clone(), true) {
-                Ok(results) => {
-                    for result in results {
-                        if let Some(achievement) =
-                            self.achievements.iter_mut().find(|a| a.name == result.name)
-                        {
-                            if result.success {
-                                achievement.status = AchievementStatus::Success;
-                                achievement.unlocked = false;
-                                success_count += 1;
-                            } else {
-                                achievement.status = AchievementStatus::Failed;
-                                fail_count += 1;
-                            }
-                        }
-                    }
-                }
-                Err(e) => {
-                    self.status = Some(Status::error(e.to_string()));
-                    for name in to_clear {
-                        if let Some(achievement) =
-                            self.achievements.iter_mut().find(|a| a.name == name)
-                        {
-                            achievement.status = AchievementStatus::Failed;
-                            fail_count += 1;
-                        }
-                    }
-                }
-            }
+        if !applied.is_empty() {
+            self.last_applied = Some(applied);
         }
 
         if fail_count == 0 && success_count > 0 {
@@ -260,30 +665,16 @@ impl App {
     }
 
     pub fn sort_achievements(&mut self) {
-        match self.sort_column {
-            SortColumn::Percentage => {
-                self.achievements.sort_by(|a, b| {
-                    let ordering = match a.percentage.partial_cmp(&b.percentage) {
-                        Some(ord) => ord,
-                        None => std::cmp::Ordering::Equal,
-                    };
-                    if self.sort_order == SortOrder::Ascending {
-                        ordering
-                    } else {
-                        ordering.reverse()
-                    }
-                });
-            }
-            SortColumn::Name => {
-                self.achievements.sort_by(|a, b| {
-                    let ordering = a.name.cmp(&b.name);
-                    if self.sort_order == SortOrder::Ascending {
-                        ordering
-                    } else {
-                        ordering.reverse()
-                    }
-                });
-            }
+        // NOTE: Sorting moves achievements out from under the view's indices,
+        // so the highlighted one is looked up again afterwards
+        let highlighted = self
+            .highlighted()
+            .map(|achievement| achievement.name.clone());
+        sort(&mut self.achievements, &self.sort_column, &self.sort_order);
+        self.refresh_view();
+
+        if let Some(position) = highlighted.and_then(|name| self.position_of(&name)) {
+            self.jump_to(position);
         }
     }
 
@@ -303,11 +694,237 @@ impl App {
         self.save_config();
     }
 
-    fn save_config(&self) {
-        let config = AppConfig {
-            sort_column: self.sort_column.clone(),
-            sort_order: self.sort_order.clone(),
-        };
-        let _ = confy::store("sam", None, config);
+    fn save_config(&mut self) {
+        let config = &mut self.config.config;
+        config.sort_column = self.sort_column.clone();
+        config.sort_order = self.sort_order.clone();
+        config.hide_spoilers = self.hide_spoilers;
+        config.show_details = self.show_details;
+        self.config.save();
+    }
+}
+
+/// How well an achievement's name, API name or description matches a
+/// query, or `None` if none of them do. Descriptions concealed as spoilers
+/// aren't searched, so a match can't give them away.
+fn match_score(achievement: &AchievementItem, query: &str, hide_spoilers: bool) -> Option<i64> {
+    let description = !(achievement.hidden && hide_spoilers);
+
+    [
+        Some(&achievement.display_name),
+        Some(&achievement.name),
+        description.then_some(&achievement.description),
+    ]
+    .into_iter()
+    .flatten()
+    .filter_map(|field| fuzzy_score(field, query))
+    .map(|found| found.score)
+    .max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::steam::{MockBackend, StatsBackend};
+    use crate::tui::AppConfig;
+    use crate::tui::worker::batch;
+
+    const FIXTURE: &str = r#"{ "apps": {
+        "480": {
+            "achievements": [
+                { "name": "ACH_WIN_ONE_GAME", "display_name": "Winner",
+                  "description": "Win one game.", "unlocked": false, "percentage": 42.0 },
+                { "name": "ACH_WIN_100_GAMES", "display_name": "Champion",
+                  "description": "Win 100 games.", "unlocked": false, "percentage": 3.5 },
+                { "name": "ACH_TRAVEL_FAR_ACCUM", "display_name": "Interstellar",
+                  "description": "Travel beyond the final frontier.", "hidden": true,
+                  "unlocked": true, "unlocked_at": 1700000000, "percentage": 12.0 },
+                { "name": "ACH_TRAVEL_FAR_SINGLE", "display_name": "Orbiter",
+                  "description": "Travel far in one game.", "unlocked": false }
+            ],
+            "failing": ["ACH_WIN_100_GAMES"]
+        },
+        "620": {
+            "achievements": [{ "name": "ACH_PORTAL", "unlocked": false }],
+            "fail_store": true
+        }
+    } }"#;
+
+    /// A mock backend and an app loaded from it, with a config that is
+    /// never written to disk.
+    fn load(id: u32) -> (MockBackend, App) {
+        let mut backend: MockBackend = serde_json::from_str(FIXTURE).unwrap();
+        let data = backend.get_achievements(id).unwrap();
+        let app = App::new(data, id, ConfigFile::in_memory(AppConfig::default()));
+        (backend, app)
+    }
+
+    fn names(app: &App) -> Vec<&str> {
+        app.visible_achievements()
+            .map(|a| a.name.as_str())
+            .collect()
+    }
+
+    fn toggle(app: &mut App, name: &str) {
+        let position = app.position_of(name).unwrap();
+        app.jump_to(position);
+        app.toggle_selection();
+    }
+
+    fn apply(backend: &mut MockBackend, app: &mut App) {
+        let changes = app.pending_changes();
+        let unlocked = batch(backend, app.app_id, changes.to_set, false);
+        let cleared = batch(backend, app.app_id, changes.to_clear, true);
+        app.apply_results(unlocked, cleared);
+    }
+
+    fn achievement<'a>(app: &'a App, name: &str) -> &'a AchievementItem {
+        app.achievements.iter().find(|a| a.name == name).unwrap()
+    }
+
+    #[test]
+    fn selection_becomes_pending_changes() {
+        let (_, mut app) = load(480);
+        assert!(app.pending_changes().is_empty());
+
+        toggle(&mut app, "ACH_WIN_ONE_GAME");
+        toggle(&mut app, "ACH_TRAVEL_FAR_ACCUM");
+        assert_eq!(
+            app.pending_changes(),
+            Changes {
+                to_set: vec!["ACH_WIN_ONE_GAME".to_string()],
+                to_clear: vec!["ACH_TRAVEL_FAR_ACCUM".to_string()],
+            }
+        );
+
+        app.undo();
+        assert!(app.pending_changes().to_clear.is_empty());
+
+        app.select_all();
+        assert_eq!(app.pending_changes().to_set.len(), 3);
+        app.deselect_all();
+        assert_eq!(
+            app.pending_changes().to_clear,
+            ["ACH_TRAVEL_FAR_ACCUM".to_string()]
+        );
+    }
+
+    #[test]
+    fn apply_results_mark_each_achievement() {
+        let (mut backend, mut app) = load(480);
+        toggle(&mut app, "ACH_WIN_ONE_GAME");
+        toggle(&mut app, "ACH_WIN_100_GAMES");
+        apply(&mut backend, &mut app);
+
+        let winner = achievement(&app, "ACH_WIN_ONE_GAME");
+        assert!(winner.unlocked);
+        assert!(winner.unlocked_at.is_some());
+        assert_eq!(winner.status, AchievementStatus::Success);
+
+        let champion = achievement(&app, "ACH_WIN_100_GAMES");
+        assert!(!champion.unlocked);
+        assert_eq!(champion.status, AchievementStatus::Failed);
+
+        assert_eq!(
+            app.status.as_ref().unwrap().message,
+            "⚠ Processed: 1 success, 1 failed"
+        );
+        assert_eq!(
+            app.last_applied.as_ref().unwrap().to_set,
+            ["ACH_WIN_ONE_GAME".to_string()]
+        );
+        // The failed one stays selected so it can be retried
+        assert_eq!(
+            app.pending_changes().to_set,
+            ["ACH_WIN_100_GAMES".to_string()]
+        );
+
+        let live = backend.get_achievements(480).unwrap().achievements;
+        assert!(
+            live.iter()
+                .any(|a| a.name == "ACH_WIN_ONE_GAME" && a.unlocked)
+        );
+    }
+
+    #[test]
+    fn a_failed_store_fails_every_achievement() {
+        let (mut backend, mut app) = load(620);
+        toggle(&mut app, "ACH_PORTAL");
+        apply(&mut backend, &mut app);
+
+        let portal = achievement(&app, "ACH_PORTAL");
+        assert!(!portal.unlocked);
+        assert_eq!(portal.status, AchievementStatus::Failed);
+        assert!(app.last_applied.is_none());
+    }
+
+    #[test]
+    fn sorting_keeps_the_highlighted_achievement() {
+        let (_, mut app) = load(480);
+        assert_eq!(
+            names(&app),
+            [
+                "ACH_WIN_ONE_GAME",
+                "ACH_TRAVEL_FAR_ACCUM",
+                "ACH_WIN_100_GAMES",
+                "ACH_TRAVEL_FAR_SINGLE"
+            ]
+        );
+
+        app.jump_to(1);
+        app.set_sort_column(SortColumn::Name);
+        assert_eq!(
+            names(&app),
+            [
+                "ACH_WIN_ONE_GAME",
+                "ACH_TRAVEL_FAR_SINGLE",
+                "ACH_TRAVEL_FAR_ACCUM",
+                "ACH_WIN_100_GAMES"
+            ]
+        );
+        assert_eq!(app.highlighted().unwrap().name, "ACH_TRAVEL_FAR_ACCUM");
+
+        app.set_sort_column(SortColumn::UnlockTime);
+        assert_eq!(names(&app)[0], "ACH_TRAVEL_FAR_ACCUM");
+        app.toggle_sort_order();
+        assert_eq!(names(&app)[3], "ACH_TRAVEL_FAR_ACCUM");
+        assert_eq!(app.config.config.sort_order, SortOrder::Ascending);
+    }
+
+    #[test]
+    fn search_cycles_through_matches() {
+        let (_, mut app) = load(480);
+
+        app.search_query = "travel".to_string();
+        assert!(app.update_search());
+        assert_eq!(app.search_matches.len(), 2);
+
+        let first = app.highlighted().unwrap().name.clone();
+        app.next_match();
+        let second = app.highlighted().unwrap().name.clone();
+        assert_ne!(first, second);
+        assert!(second.starts_with("ACH_TRAVEL_FAR"));
+        app.next_match();
+        assert_eq!(app.highlighted().unwrap().name, first);
+
+        app.search_query = "zzz".to_string();
+        assert!(!app.update_search());
+    }
+
+    #[test]
+    fn hidden_descriptions_are_not_searched_as_spoilers() {
+        let (_, mut app) = load(480);
+
+        app.filter_query = "frontier".to_string();
+        app.update_filter();
+        assert_eq!(names(&app), ["ACH_TRAVEL_FAR_ACCUM"]);
+
+        app.toggle_spoilers();
+        assert!(names(&app).is_empty());
+
+        app.filter_query.clear();
+        app.update_filter();
+        app.search_query = "frontier".to_string();
+        assert!(!app.update_search());
     }
 }
@@ -1,10 +1,29 @@
 use super::models::{SortColumn, SortOrder};
+use super::theme::RarityBounds;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
 
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AppConfig {
     pub sort_column: SortColumn,
     pub sort_order: SortOrder,
+    pub hide_spoilers: bool,
+    pub show_details: bool,
+    /// `dark`, `light`, `high-contrast` or `monochrome`. Unset picks dark,
+    /// or monochrome when colors are unwanted or unsupported.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+    /// How to draw achievement icons: `auto`, `kitty`, `sixel`, `blocks`
+    /// or `off`. Unset is the same as `auto`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icons: Option<String>,
+    /// Colors by theme role, replacing the theme's own.
+    pub colors: BTreeMap<String, String>,
+    pub rarity: RarityBounds,
+    /// Keys for each action by name, replacing that action's defaults.
+    pub keys: BTreeMap<String, Vec<String>>,
 }
 
 impl Default for AppConfig {
@@ -12,6 +31,67 @@ impl Default for AppConfig {
         Self {
             sort_column: SortColumn::Percentage,
             sort_order: SortOrder::Descending,
+            hide_spoilers: false,
+            show_details: false,
+            theme: None,
+            icons: None,
+            colors: BTreeMap::new(),
+            rarity: RarityBounds::default(),
+            keys: BTreeMap::new(),
+        }
+    }
+}
+
+/// The config as loaded at startup, and whether it may be written back.
+#[derive(Debug, Default)]
+pub struct ConfigFile {
+    pub config: AppConfig,
+    /// False if the file didn't parse, so saving settings can't wipe it out,
+    /// or if the config was never loaded from disk.
+    writable: bool,
+}
+
+impl ConfigFile {
+    /// Reads the config file, falling back to the defaults with a warning
+    /// if it doesn't parse.
+    pub fn load() -> (Self, Option<String>) {
+        match confy::load("sam", None) {
+            Ok(config) => (
+                Self {
+                    config,
+                    writable: true,
+                },
+                None,
+            ),
+            Err(e) => {
+                // NOTE: TOML errors span several lines, the first says where
+                let cause = e.source().map(|cause| cause.to_string());
+                let reason = match cause.as_deref().and_then(|cause| cause.lines().next()) {
+                    Some(location) => format!("{} ({})", e, location),
+                    None => e.to_string(),
+                };
+                (
+                    Self::in_memory(AppConfig::default()),
+                    Some(format!(
+                        "Config file ignored, fix it to save settings: {}",
+                        reason
+                    )),
+                )
+            }
+        }
+    }
+
+    /// A config that is never written to disk.
+    pub fn in_memory(config: AppConfig) -> Self {
+        Self {
+            config,
+            writable: false,
+        }
+    }
+
+    pub fn save(&self) {
+        if self.writable {
+            let _ = confy::store("sam", None, &self.config);
         }
     }
 }
@@ -0,0 +1,95 @@
+/// How many selection edits are kept for undo.
+const HISTORY_LIMIT: usize = 100;
+
+/// One selection edit, as the achievements whose checkbox it flipped.
+/// Flipping the same achievements again undoes it, and again redoes it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Edit {
+    pub label: &'static str,
+    pub names: Vec<String>,
+}
+
+/// Undo and redo stacks for selection edits.
+#[derive(Debug, Default)]
+pub struct History {
+    undo: Vec<Edit>,
+    redo: Vec<Edit>,
+}
+
+impl History {
+    /// Records a new edit, dropping anything that could have been redone.
+    pub fn record(&mut self, edit: Edit) {
+        self.redo.clear();
+        self.undo.push(edit);
+        if self.undo.len() > HISTORY_LIMIT {
+            self.undo.remove(0);
+        }
+    }
+
+    /// The edit to flip back, which can then be redone.
+    pub fn undo(&mut self) -> Option<&Edit> {
+        let edit = self.undo.pop()?;
+        self.redo.push(edit);
+        self.redo.last()
+    }
+
+    /// The most recently undone edit to flip again.
+    pub fn redo(&mut self) -> Option<&Edit> {
+        let edit = self.redo.pop()?;
+        self.undo.push(edit);
+        self.undo.last()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(label: &'static str) -> Edit {
+        Edit {
+            label,
+            names: vec![label.to_string()],
+        }
+    }
+
+    #[test]
+    fn undoes_and_redoes_in_order() {
+        let mut history = History::default();
+        history.record(edit("first"));
+        history.record(edit("second"));
+
+        assert_eq!(history.undo().map(|e| e.label), Some("second"));
+        assert_eq!(history.undo().map(|e| e.label), Some("first"));
+        assert_eq!(history.undo(), None);
+
+        assert_eq!(history.redo().map(|e| e.label), Some("first"));
+        assert_eq!(history.redo().map(|e| e.label), Some("second"));
+        assert_eq!(history.redo(), None);
+    }
+
+    #[test]
+    fn new_edits_clear_redo() {
+        let mut history = History::default();
+        history.record(edit("first"));
+        history.undo();
+        history.record(edit("second"));
+
+        assert_eq!(history.redo(), None);
+        assert_eq!(history.undo().map(|e| e.label), Some("second"));
+        assert_eq!(history.undo(), None);
+    }
+
+    #[test]
+    fn forgets_the_oldest_edits() {
+        let mut history = History::default();
+        for _ in 0..HISTORY_LIMIT + 5 {
+            history.record(edit("edit"));
+        }
+
+        let mut undone = 0;
+        while history.undo().is_some() {
+            undone += 1;
+        }
+        assert_eq!(undone, HISTORY_LIMIT);
+    }
+}
@@ -0,0 +1,337 @@
+//! Getting icons onto the screen: inline images for terminals that speak
+//! the kitty or sixel graphics protocols, half blocks everywhere else.
+
+use super::Icon;
+use crate::tui::AppConfig;
+use crate::tui::theme::prefers_monochrome;
+use crossterm::{cursor::MoveTo, queue, terminal};
+use openssl::base64;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Color;
+use ratatui::widgets::Widget;
+use std::env;
+use std::io::{self, Write};
+
+pub const PROTOCOLS: [&str; 5] = ["auto", "kitty", "sixel", "blocks", "off"];
+
+/// Kitty image id for the icon, so it can be replaced and deleted.
+const KITTY_IMAGE_ID: u32 = 1;
+
+/// Cell size to assume when the terminal doesn't report one.
+const FALLBACK_CELL: (u16, u16) = (8, 16);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Protocol {
+    Kitty,
+    Sixel,
+    /// Two pixels per cell using `▀` with different colors above and below.
+    Blocks,
+}
+
+impl Protocol {
+    /// The configured protocol, `None` if icons are off.
+    pub fn load(config: &AppConfig) -> (Option<Self>, Vec<String>) {
+        match config.icons.as_deref() {
+            None | Some("auto") => (detect(), Vec::new()),
+            Some("kitty") => (Some(Protocol::Kitty), Vec::new()),
+            Some("sixel") => (Some(Protocol::Sixel), Vec::new()),
+            Some("blocks") => (Some(Protocol::Blocks), Vec::new()),
+            Some("off") => (None, Vec::new()),
+            Some(other) => (
+                detect(),
+                vec![format!(
+                    "Unknown icon protocol: {} (expected one of {})",
+                    other,
+                    PROTOCOLS.join(", ")
+                )],
+            ),
+        }
+    }
+}
+
+/// Guesses what the terminal can show from its environment, since asking
+/// it would mean reading replies from stdin.
+fn detect() -> Option<Protocol> {
+    let var = |name: &str| env::var(name).unwrap_or_default();
+    let term = var("TERM");
+    let program = var("TERM_PROGRAM");
+
+    // NOTE: tmux passes neither protocol through without extra setup
+    if env::var_os("TMUX").is_none() {
+        if env::var_os("KITTY_WINDOW_ID").is_some()
+            || term.contains("kitty")
+            || term.contains("ghostty")
+            || matches!(program.as_str(), "WezTerm" | "ghostty")
+        {
+            return Some(Protocol::Kitty);
+        }
+        if term.contains("sixel")
+            || term.starts_with("foot")
+            || term.starts_with("mlterm")
+            || program == "iTerm.app"
+            || env::var_os("WT_SESSION").is_some()
+        {
+            return Some(Protocol::Sixel);
+        }
+    }
+
+    // Half blocks need truecolor to look like anything
+    (!prefers_monochrome()).then_some(Protocol::Blocks)
+}
+
+/// Draws an icon into the buffer with half blocks.
+pub struct IconBlocks<'a>(pub &'a Icon);
+
+impl Widget for IconBlocks<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.is_empty() {
+            return;
+        }
+
+        let Ok(icon) = self.0.resize(area.width as u32, area.height as u32 * 2) else {
+            return;
+        };
+        let color = |x: u16, y: u16| {
+            let [r, g, b] = icon.pixel(x as u32, y as u32);
+            Color::Rgb(r, g, b)
+        };
+        for y in 0..area.height {
+            for x in 0..area.width {
+                buf[(area.x + x, area.y + y)]
+                    .set_symbol("▀")
+                    .set_fg(color(x, y * 2))
+                    .set_bg(color(x, y * 2 + 1));
+            }
+        }
+    }
+}
+
+/// An icon to show as an inline image, and where.
+#[derive(Clone, Debug)]
+pub struct Placement {
+    /// The icon's schema file name, to tell icons apart cheaply.
+    pub file: String,
+    pub area: Rect,
+    /// The whole frame, since a resize clears the screen.
+    pub screen: Rect,
+    pub icon: Icon,
+}
+
+impl Placement {
+    fn same(&self, other: &Placement) -> bool {
+        self.file == other.file && self.area == other.area && self.screen == other.screen
+    }
+}
+
+/// Keeps the inline image on screen in step with what the last frame
+/// wanted. Images live outside ratatui's buffer, so they are written
+/// straight to the terminal after each draw.
+pub struct Graphics {
+    protocol: Option<Protocol>,
+    shown: Option<Placement>,
+}
+
+impl Graphics {
+    pub fn new(protocol: Option<Protocol>) -> Self {
+        Self {
+            protocol,
+            shown: None,
+        }
+    }
+
+    /// Shows `wanted` in place of whatever is shown now. Returns true if
+    /// the screen must be cleared and redrawn to get rid of an old image.
+    pub fn sync(&mut self, out: &mut impl Write, wanted: Option<Placement>) -> io::Result<bool> {
+        let unchanged = match (&self.shown, &wanted) {
+            (Some(shown), Some(wanted)) => shown.same(wanted),
+            (None, None) => true,
+            _ => false,
+        };
+        if unchanged {
+            return Ok(false);
+        }
+
+        match self.protocol {
+            Some(Protocol::Kitty) => {
+                if self.shown.is_some() {
+                    write!(out, "\x1b_Ga=d,d=I,i={},q=2\x1b\\", KITTY_IMAGE_ID)?;
+                }
+                if let Some(placement) = &wanted {
+                    queue!(out, MoveTo(placement.area.x, placement.area.y))?;
+                    write_kitty(out, placement)?;
+                }
+            }
+            Some(Protocol::Sixel) => {
+                // Sixels are just pixels in the cells, so an image that is
+                // replaced in place is simply drawn over
+                let moved = self
+                    .shown
+                    .as_ref()
+                    .is_some_and(|shown| wanted.as_ref().is_none_or(|w| w.area != shown.area));
+                if moved {
+                    self.shown = None;
+                    return Ok(true);
+                }
+                if let Some(placement) = &wanted {
+                    queue!(out, MoveTo(placement.area.x, placement.area.y))?;
+                    write_sixel(out, placement)?;
+                }
+            }
+            Some(Protocol::Blocks) | None => {}
+        }
+        out.flush()?;
+
+        self.shown = wanted;
+        Ok(false)
+    }
+}
+
+fn write_kitty(out: &mut impl Write, placement: &Placement) -> io::Result<()> {
+    let icon = &placement.icon;
+    let data = base64::encode_block(&icon.pixels);
+    let chunks: Vec<&[u8]> = data.as_bytes().chunks(4096).collect();
+
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = (i + 1 < chunks.len()) as u8;
+        if i == 0 {
+            write!(
+                out,
+                "\x1b_Ga=T,f=24,s={},v={},c={},r={},i={},C=1,q=2,m={};",
+                icon.width,
+                icon.height,
+                placement.area.width,
+                placement.area.height,
+                KITTY_IMAGE_ID,
+                more
+            )?;
+        } else {
+            write!(out, "\x1b_Gm={};", more)?;
+        }
+        out.write_all(chunk)?;
+        write!(out, "\x1b\\")?;
+    }
+    Ok(())
+}
+
+fn write_sixel(out: &mut impl Write, placement: &Placement) -> io::Result<()> {
+    let (cell_width, cell_height) = terminal::window_size()
+        .ok()
+        .filter(|size| size.width > 0 && size.height > 0 && size.columns > 0 && size.rows > 0)
+        .map(|size| (size.width / size.columns, size.height / size.rows))
+        .unwrap_or(FALLBACK_CELL);
+
+    let width = (placement.area.width * cell_width) as u32;
+    let height = (placement.area.height * cell_height) as u32;
+    // NOTE: Rounded down to whole sixels so the image can't spill into the
+    // row below
+    let height = height - height % 6;
+    if width == 0 || height == 0 {
+        return Ok(());
+    }
+
+    let icon = placement
+        .icon
+        .resize(width, height)
+        .map_err(io::Error::other)?;
+    out.write_all(sixel(&icon).as_bytes())
+}
+
+/// Encodes an icon as sixels, quantized to a 6x6x6 color cube.
+fn sixel(icon: &Icon) -> String {
+    let level = |channel: u8| (channel as u32 * 5 + 127) / 255;
+    let indices: Vec<u32> = icon
+        .pixels
+        .chunks(3)
+        .map(|rgb| level(rgb[0]) * 36 + level(rgb[1]) * 6 + level(rgb[2]))
+        .collect();
+
+    let mut used = [false; 216];
+    for &index in &indices {
+        used[index as usize] = true;
+    }
+
+    let mut data = format!("\x1bPq\"1;1;{};{}", icon.width, icon.height);
+    for (index, _) in used.iter().enumerate().filter(|(_, used)| **used) {
+        let percent = |level: usize| level * 100 / 5;
+        data += &format!(
+            "#{};2;{};{};{}",
+            index,
+            percent(index / 36),
+            percent(index / 6 % 6),
+            percent(index % 6)
+        );
+    }
+
+    let (width, height) = (icon.width as usize, icon.height as usize);
+    for band in (0..height).step_by(6) {
+        let rows = band..(band + 6).min(height);
+        let mut colors: Vec<u32> = rows
+            .clone()
+            .flat_map(|y| indices[y * width..(y + 1) * width].iter().copied())
+            .collect();
+        colors.sort_unstable();
+        colors.dedup();
+
+        for (n, &color) in colors.iter().enumerate() {
+            if n > 0 {
+                data.push('$');
+            }
+            data += &format!("#{}", color);
+
+            let mut run: Option<(char, usize)> = None;
+            for x in 0..width {
+                let bits = rows
+                    .clone()
+                    .filter(|&y| indices[y * width + x] == color)
+                    .fold(0, |bits, y| bits | 1 << (y - band));
+                let symbol = char::from(63 + bits as u8);
+                run = match run {
+                    Some((previous, count)) if previous == symbol => Some((symbol, count + 1)),
+                    Some(previous) => {
+                        push_run(&mut data, previous);
+                        Some((symbol, 1))
+                    }
+                    None => Some((symbol, 1)),
+                };
+            }
+            if let Some(run) = run {
+                push_run(&mut data, run);
+            }
+        }
+        data.push('-');
+    }
+
+    data + "\x1b\\"
+}
+
+fn push_run(data: &mut String, (symbol, count): (char, usize)) {
+    if count > 3 {
+        data.push_str(&format!("!{}{}", count, symbol));
+    } else {
+        data.extend(std::iter::repeat_n(symbol, count));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sixel_encodes_bands_and_runs() {
+        let icon = Icon {
+            width: 5,
+            height: 2,
+            pixels: [[255, 0, 0]; 5]
+                .into_iter()
+                .chain([[0, 0, 255]; 5])
+                .flatten()
+                .collect(),
+        };
+
+        assert_eq!(
+            sixel(&icon),
+            "\x1bPq\"1;1;5;2#5;2;0;0;100#180;2;100;0;0#5!5A$#180!5@-\x1b\\"
+        );
+    }
+}
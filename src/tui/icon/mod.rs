@@ -0,0 +1,295 @@
+mod graphics;
+
+pub use graphics::{Graphics, IconBlocks, Placement, Protocol};
+
+use crate::library::steam_root;
+use anyhow::{Context, Result, bail};
+use jpeg_decoder::{Decoder, PixelFormat};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Cells the icon takes up in the details panel. Cells are about twice as
+/// tall as they are wide, so this is roughly square.
+pub const ICON_COLS: u16 = 16;
+pub const ICON_ROWS: u16 = 8;
+
+/// A decoded RGB image.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Icon {
+    pub width: u32,
+    pub height: u32,
+    /// Three bytes per pixel, row by row.
+    pub pixels: Vec<u8>,
+}
+
+impl Icon {
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 3] {
+        let offset = (y as usize * self.width as usize + x as usize) * 3;
+        [
+            self.pixels[offset],
+            self.pixels[offset + 1],
+            self.pixels[offset + 2],
+        ]
+    }
+
+    /// Scales to `width` by `height`, averaging the source pixels each
+    /// target pixel covers.
+    pub fn resize(&self, width: u32, height: u32) -> Result<Icon> {
+        let mut pixels = Vec::with_capacity(byte_len(width, height)?);
+        for y in 0..height {
+            let top = y * self.height / height;
+            let bottom = ((y + 1) * self.height / height).max(top + 1);
+            for x in 0..width {
+                let left = x * self.width / width;
+                let right = ((x + 1) * self.width / width).max(left + 1);
+
+                let mut sum = [0u32; 3];
+                for sy in top..bottom {
+                    for sx in left..right {
+                        for (total, channel) in sum.iter_mut().zip(self.pixel(sx, sy)) {
+                            *total += channel as u32;
+                        }
+                    }
+                }
+                let count = (bottom - top) * (right - left);
+                pixels.extend(sum.map(|total| (total / count) as u8));
+            }
+        }
+
+        Ok(Icon {
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    pub fn decode_jpeg(data: &[u8]) -> Result<Icon> {
+        let mut decoder = Decoder::new(data);
+        let decoded = decoder.decode().context("Invalid JPEG")?;
+        let info = decoder.info().context("JPEG has no frame")?;
+
+        let pixels = match info.pixel_format {
+            PixelFormat::RGB24 => decoded,
+            PixelFormat::L8 => decoded.iter().flat_map(|&level| [level; 3]).collect(),
+            format => bail!("Unsupported JPEG pixel format: {:?}", format),
+        };
+
+        Ok(Icon {
+            width: info.width.into(),
+            height: info.height.into(),
+            pixels,
+        })
+    }
+
+    /// Reads a binary PPM (`P6`), the format icons are cached in.
+    pub fn from_ppm(data: &[u8]) -> Result<Icon> {
+        let mut fields = Vec::with_capacity(4);
+        let mut pos = 0;
+        while fields.len() < 4 {
+            while data.get(pos).is_some_and(|b| b.is_ascii_whitespace()) {
+                pos += 1;
+            }
+            let start = pos;
+            while data.get(pos).is_some_and(|b| !b.is_ascii_whitespace()) {
+                pos += 1;
+            }
+            if start == pos {
+                bail!("Truncated PPM header");
+            }
+            fields.push(std::str::from_utf8(&data[start..pos])?);
+        }
+
+        let [magic, width, height, max] = fields[..] else {
+            unreachable!()
+        };
+        if magic != "P6" || max != "255" {
+            bail!("Unsupported PPM format");
+        }
+        let width: u32 = width.parse().context("Invalid PPM width")?;
+        let height: u32 = height.parse().context("Invalid PPM height")?;
+
+        let len = byte_len(width, height)?;
+        let pixels = data
+            .get(pos + 1..)
+            .filter(|pixels| pixels.len() == len)
+            .context("PPM size doesn't match its header")?;
+
+        Ok(Icon {
+            width,
+            height,
+            pixels: pixels.to_vec(),
+        })
+    }
+
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let mut data = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        data.extend(&self.pixels);
+        data
+    }
+}
+
+/// Bytes taken by `width` by `height` RGB pixels.
+fn byte_len(width: u32, height: u32) -> Result<usize> {
+    (width as usize)
+        .checked_mul(height as usize)
+        .and_then(|pixels| pixels.checked_mul(3))
+        .with_context(|| format!("Image too large: {}x{}", width, height))
+}
+
+/// Icons for one app, decoded once and kept in memory. Decoded icons are
+/// also written to disk so later runs don't decode them again.
+#[derive(Debug)]
+pub struct IconCache {
+    app_id: u32,
+    loaded: HashMap<String, Option<Icon>>,
+}
+
+impl IconCache {
+    pub fn new(app_id: u32) -> Self {
+        Self {
+            app_id,
+            loaded: HashMap::new(),
+        }
+    }
+
+    /// The icon with the schema file name `file`, if Steam has it cached.
+    /// Missing or broken icons are remembered so they are only tried once.
+    pub fn get(&mut self, file: &str) -> Option<&Icon> {
+        let app_id = self.app_id;
+        self.loaded
+            .entry(file.to_string())
+            .or_insert_with(|| load(app_id, file).ok())
+            .as_ref()
+    }
+}
+
+/// Where decoded icons are cached, next to the config file.
+fn cache_dir(app_id: u32) -> Result<PathBuf> {
+    let config = confy::get_configuration_file_path("sam", None)?;
+    Ok(config
+        .parent()
+        .map(|dir| dir.join("icons"))
+        .unwrap_or_else(|| PathBuf::from("icons"))
+        .join(app_id.to_string()))
+}
+
+fn load(app_id: u32, file: &str) -> Result<Icon> {
+    // The name comes from the schema and ends up in a path
+    if file.is_empty()
+        || file.starts_with('.')
+        || !file
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+    {
+        bail!("Invalid icon name: {}", file);
+    }
+
+    let stem = file.rsplit_once('.').map_or(file, |(stem, _)| stem);
+    let cached = cache_dir(app_id)?.join(format!("{}.ppm", stem));
+    if let Ok(data) = fs::read(&cached)
+        && let Ok(icon) = Icon::from_ppm(&data)
+    {
+        return Ok(icon);
+    }
+
+    let root = steam_root().context("Steam installation not found")?;
+    let library = root.join("appcache/librarycache");
+    // NOTE: Newer clients keep icons in a folder per app, older ones don't
+    let data = [
+        library.join(app_id.to_string()).join(file),
+        library.join(file),
+    ]
+    .iter()
+    .find_map(|path| fs::read(path).ok())
+    .with_context(|| format!("Icon {} is not cached by Steam", file))?;
+    let icon = Icon::decode_jpeg(&data)?;
+
+    if let Some(dir) = cached.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    let _ = fs::write(&cached, icon.to_ppm());
+
+    Ok(icon)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checkerboard() -> Icon {
+        let pixels = (0..4 * 4)
+            .flat_map(|i| {
+                let (x, y) = (i % 4, i / 4);
+                if (x / 2 + y / 2) % 2 == 0 {
+                    [255, 255, 255]
+                } else {
+                    [0, 0, 0]
+                }
+            })
+            .collect();
+        Icon {
+            width: 4,
+            height: 4,
+            pixels,
+        }
+    }
+
+    #[test]
+    fn ppm_round_trips() {
+        let icon = checkerboard();
+        assert_eq!(Icon::from_ppm(&icon.to_ppm()).unwrap(), icon);
+        assert!(Icon::from_ppm(b"P6\n4 4\n255\n\0\0\0").is_err());
+        assert!(Icon::from_ppm(b"P3\n1 1\n255\n0 0 0").is_err());
+
+        let error = Icon::from_ppm(b"P6\n4294967295 4294967295\n255\n\0").unwrap_err();
+        assert_eq!(error.to_string(), "Image too large: 4294967295x4294967295");
+        assert!(icon.resize(u32::MAX, u32::MAX).is_err());
+    }
+
+    #[test]
+    fn resize_averages_and_repeats() {
+        let icon = checkerboard();
+
+        let small = icon.resize(2, 2).unwrap();
+        assert_eq!(small.pixel(0, 0), [255, 255, 255]);
+        assert_eq!(small.pixel(1, 0), [0, 0, 0]);
+
+        let tiny = icon.resize(1, 1).unwrap();
+        assert_eq!(tiny.pixel(0, 0), [127, 127, 127]);
+
+        let large = icon.resize(8, 8).unwrap();
+        assert_eq!(large.pixel(3, 3), [255, 255, 255]);
+        assert_eq!(large.pixel(4, 3), [0, 0, 0]);
+    }
+
+    #[test]
+    fn decodes_baseline_jpeg() {
+        let icon = Icon::decode_jpeg(include_bytes!("../../../tests/fixtures/icon.jpg")).unwrap();
+        assert_eq!((icon.width, icon.height), (16, 16));
+
+        let close = |actual: [u8; 3], expected: [u8; 3]| {
+            actual
+                .iter()
+                .zip(expected)
+                .all(|(&a, e)| a.abs_diff(e) <= 12)
+        };
+        assert!(
+            close(icon.pixel(2, 2), [220, 40, 40]),
+            "{:?}",
+            icon.pixel(2, 2)
+        );
+        assert!(
+            close(icon.pixel(13, 2), [40, 40, 220]),
+            "{:?}",
+            icon.pixel(13, 2)
+        );
+        assert!(
+            close(icon.pixel(8, 13), [40, 200, 40]),
+            "{:?}",
+            icon.pixel(8, 13)
+        );
+
+        assert!(Icon::decode_jpeg(b"not a jpeg").is_err());
+    }
+}
@@ -0,0 +1,584 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+
+/// Where a key is pressed. Actions only conflict within the same context.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Context {
+    Achievements,
+    /// Checked before `Achievements` while a search has matches.
+    Matches,
+    Stats,
+    Review,
+}
+
+/// Everything a key can be bound to, in the order the Controls help lists
+/// them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Action {
+    NextMatch,
+    PrevMatch,
+    Down,
+    Up,
+    PageDown,
+    PageUp,
+    Top,
+    Bottom,
+    EditStat,
+    DiscardStat,
+    Toggle,
+    SelectAll,
+    DeselectAll,
+    Undo,
+    Redo,
+    RevertApply,
+    SortByPercentage,
+    SortByName,
+    SortByUnlockTime,
+    SortOrder,
+    Visibility,
+    Spoilers,
+    Details,
+    Confirm,
+    ResetAll,
+    SaveSnapshot,
+    LoadSnapshot,
+    Search,
+    Filter,
+    ShowLocked,
+    ShowUnlocked,
+    ShowPending,
+    ShowFailed,
+    SwitchTab,
+    SwitchApp,
+    Back,
+    Quit,
+}
+
+impl Action {
+    pub const ALL: [Action; 37] = [
+        Action::NextMatch,
+        Action::PrevMatch,
+        Action::Down,
+        Action::Up,
+        Action::PageDown,
+        Action::PageUp,
+        Action::Top,
+        Action::Bottom,
+        Action::EditStat,
+        Action::DiscardStat,
+        Action::Toggle,
+        Action::SelectAll,
+        Action::DeselectAll,
+        Action::Undo,
+        Action::Redo,
+        Action::RevertApply,
+        Action::SortByPercentage,
+        Action::SortByName,
+        Action::SortByUnlockTime,
+        Action::SortOrder,
+        Action::Visibility,
+        Action::Spoilers,
+        Action::Details,
+        Action::Confirm,
+        Action::ResetAll,
+        Action::SaveSnapshot,
+        Action::LoadSnapshot,
+        Action::Search,
+        Action::Filter,
+        Action::ShowLocked,
+        Action::ShowUnlocked,
+        Action::ShowPending,
+        Action::ShowFailed,
+        Action::SwitchTab,
+        Action::SwitchApp,
+        Action::Back,
+        Action::Quit,
+    ];
+
+    /// The name used for this action in the config file.
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::NextMatch => "next-match",
+            Action::PrevMatch => "prev-match",
+            Action::Down => "down",
+            Action::Up => "up",
+            Action::PageDown => "page-down",
+            Action::PageUp => "page-up",
+            Action::Top => "top",
+            Action::Bottom => "bottom",
+            Action::EditStat => "edit-stat",
+            Action::DiscardStat => "discard-stat",
+            Action::Toggle => "toggle",
+            Action::SelectAll => "select-all",
+            Action::DeselectAll => "deselect-all",
+            Action::Undo => "undo",
+            Action::Redo => "redo",
+            Action::RevertApply => "revert-apply",
+            Action::SortByPercentage => "sort-by-percentage",
+            Action::SortByName => "sort-by-name",
+            Action::SortByUnlockTime => "sort-by-unlock-time",
+            Action::SortOrder => "sort-order",
+            Action::Visibility => "visibility",
+            Action::Spoilers => "spoilers",
+            Action::Details => "details",
+            Action::Confirm => "confirm",
+            Action::ResetAll => "reset-all",
+            Action::SaveSnapshot => "save-snapshot",
+            Action::LoadSnapshot => "load-snapshot",
+            Action::Search => "search",
+            Action::Filter => "filter",
+            Action::ShowLocked => "show-locked",
+            Action::ShowUnlocked => "show-unlocked",
+            Action::ShowPending => "show-pending",
+            Action::ShowFailed => "show-failed",
+            Action::SwitchTab => "switch-tab",
+            Action::SwitchApp => "switch-app",
+            Action::Back => "back",
+            Action::Quit => "quit",
+        }
+    }
+
+    fn default_keys(self) -> &'static [&'static str] {
+        match self {
+            Action::NextMatch => &["n"],
+            Action::PrevMatch => &["N"],
+            Action::Down => &["j", "Down"],
+            Action::Up => &["k", "Up"],
+            Action::PageDown => &["ctrl-n", "PageDown"],
+            Action::PageUp => &["ctrl-p", "PageUp"],
+            Action::Top => &["g"],
+            Action::Bottom => &["G"],
+            Action::EditStat => &["e"],
+            Action::DiscardStat => &["x"],
+            Action::Toggle => &["Space"],
+            Action::SelectAll => &["a"],
+            Action::DeselectAll => &["d"],
+            Action::Undo => &["u"],
+            Action::Redo => &["ctrl-r"],
+            Action::RevertApply => &["r"],
+            Action::SortByPercentage => &["p"],
+            Action::SortByName => &["n"],
+            Action::SortByUnlockTime => &["t"],
+            Action::SortOrder => &["o"],
+            Action::Visibility => &["v"],
+            Action::Spoilers => &["h"],
+            Action::Details => &["D"],
+            Action::Confirm => &["Enter"],
+            Action::ResetAll => &["R"],
+            Action::SaveSnapshot => &["S"],
+            Action::LoadSnapshot => &["L"],
+            Action::Search => &["/"],
+            Action::Filter => &["f"],
+            Action::ShowLocked => &["1"],
+            Action::ShowUnlocked => &["2"],
+            Action::ShowPending => &["3"],
+            Action::ShowFailed => &["4"],
+            Action::SwitchTab => &["Tab"],
+            Action::SwitchApp => &["i"],
+            Action::Back => &["Esc"],
+            Action::Quit => &["q"],
+        }
+    }
+
+    fn contexts(self) -> &'static [Context] {
+        use Context::*;
+        match self {
+            Action::NextMatch | Action::PrevMatch => &[Matches],
+            Action::Down | Action::Up | Action::Confirm | Action::Back | Action::Quit => {
+                &[Achievements, Stats, Review]
+            }
+            Action::Toggle => &[Achievements, Review],
+            Action::Top
+            | Action::Bottom
+            | Action::ResetAll
+            | Action::SwitchTab
+            | Action::SwitchApp => &[Achievements, Stats],
+            Action::EditStat | Action::DiscardStat => &[Stats],
+            _ => &[Achievements],
+        }
+    }
+
+    /// What the Controls help calls this action. Related actions share a
+    /// label so their keys are listed together.
+    pub fn label(self, context: Context) -> &'static str {
+        match self {
+            Action::NextMatch | Action::PrevMatch => "Next/Prev Match",
+            Action::Down => "Down",
+            Action::Up => "Up",
+            Action::PageDown => "Page Down",
+            Action::PageUp => "Page Up",
+            Action::Top => "Top",
+            Action::Bottom => "Bottom",
+            Action::EditStat => "Edit",
+            Action::DiscardStat => "Discard",
+            Action::Toggle if context == Context::Review => "Include/Exclude",
+            Action::Toggle => "Toggle",
+            Action::SelectAll => "Select All",
+            Action::DeselectAll => "Deselect All",
+            Action::Undo | Action::Redo => "Undo/Redo",
+            Action::RevertApply => "Revert Apply",
+            Action::SortByPercentage | Action::SortByName | Action::SortByUnlockTime => {
+                "Sort Column"
+            }
+            Action::SortOrder => "Sort Order",
+            Action::Visibility => "Visibility",
+            Action::Spoilers => "Spoilers",
+            Action::Details => "Details",
+            Action::Confirm if context == Context::Achievements => "Review",
+            Action::Confirm => "Apply",
+            Action::ResetAll => "Reset All",
+            Action::SaveSnapshot | Action::LoadSnapshot => "Save/Load Snapshot",
+            Action::Search => "Search",
+            Action::Filter => "Filter",
+            Action::ShowLocked
+            | Action::ShowUnlocked
+            | Action::ShowPending
+            | Action::ShowFailed => "Locked/Unlocked/Pending/Failed",
+            Action::SwitchTab if context == Context::Stats => "Achievements",
+            Action::SwitchTab => "Stats",
+            Action::SwitchApp => "Switch App",
+            Action::Back | Action::Quit if context == Context::Review => "Back",
+            Action::Back | Action::Quit => "Quit",
+        }
+    }
+}
+
+/// A key plus the modifiers that must be held with it, written like `j`,
+/// `G`, `ctrl-r`, `alt-Enter` or `PageDown` in the config file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct KeyBinding {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+/// Modifiers that are part of a binding. Shift is left out because it is
+/// already in the character (`G` rather than `shift-g`).
+const BOUND_MODIFIERS: KeyModifiers = KeyModifiers::CONTROL.union(KeyModifiers::ALT);
+
+impl KeyBinding {
+    pub fn parse(source: &str) -> Option<Self> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = source;
+
+        loop {
+            let lower = rest.to_ascii_lowercase();
+            if rest.chars().count() > 1 && lower.starts_with("ctrl-") {
+                modifiers |= KeyModifiers::CONTROL;
+                rest = &rest["ctrl-".len()..];
+            } else if rest.chars().count() > 1 && lower.starts_with("alt-") {
+                modifiers |= KeyModifiers::ALT;
+                rest = &rest["alt-".len()..];
+            } else {
+                break;
+            }
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_ascii_lowercase().as_str() {
+                "space" => KeyCode::Char(' '),
+                "enter" => KeyCode::Enter,
+                "esc" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" => KeyCode::Insert,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" | "pgup" => KeyCode::PageUp,
+                "pagedown" | "pgdn" => KeyCode::PageDown,
+                name => KeyCode::F(name.strip_prefix('f')?.parse().ok()?),
+            },
+        };
+
+        Some(Self { code, modifiers })
+    }
+
+    fn matches(&self, key: &KeyEvent) -> bool {
+        self.code == key.code && self.modifiers == key.modifiers & BOUND_MODIFIERS
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "^")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt-")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::Up => write!(f, "↑"),
+            KeyCode::Down => write!(f, "↓"),
+            KeyCode::Left => write!(f, "←"),
+            KeyCode::Right => write!(f, "→"),
+            KeyCode::PageUp => write!(f, "PgUp"),
+            KeyCode::PageDown => write!(f, "PgDn"),
+            KeyCode::Delete => write!(f, "Del"),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+/// The keys bound to each action: the defaults, with whatever the config
+/// file's `[keys]` table overrides.
+#[derive(Debug)]
+pub struct Keymap {
+    bindings: BTreeMap<Action, Vec<KeyBinding>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let bindings = Action::ALL
+            .into_iter()
+            .map(|action| {
+                let keys = action
+                    .default_keys()
+                    .iter()
+                    .filter_map(|key| KeyBinding::parse(key))
+                    .collect();
+                (action, keys)
+            })
+            .collect();
+        Self { bindings }
+    }
+}
+
+impl Keymap {
+    /// Builds the keymap from config overrides, returning a warning for
+    /// each entry that couldn't be used. An action given an empty list is
+    /// unbound.
+    pub fn with_overrides(overrides: &BTreeMap<String, Vec<String>>) -> (Self, Vec<String>) {
+        let mut keymap = Self::default();
+        let mut warnings = Vec::new();
+
+        for (name, keys) in overrides {
+            let Some(action) = Action::ALL.into_iter().find(|a| a.name() == name) else {
+                warnings.push(format!("Unknown action in keymap: {}", name));
+                continue;
+            };
+
+            let bindings = keys
+                .iter()
+                .filter_map(|key| {
+                    let binding = KeyBinding::parse(key);
+                    if binding.is_none() {
+                        warnings.push(format!("Unknown key for {}: {}", name, key));
+                    }
+                    binding
+                })
+                .collect();
+            keymap.bindings.insert(action, bindings);
+        }
+
+        (keymap, warnings)
+    }
+
+    /// The action `key` triggers in `context`, if any.
+    pub fn action(&self, context: Context, key: &KeyEvent) -> Option<Action> {
+        self.actions(context)
+            .find(|(_, keys)| keys.iter().any(|binding| binding.matches(key)))
+            .map(|(action, _)| action)
+    }
+
+    /// Keys bound to more than one action in the same context. Match keys
+    /// are checked against the achievements table too, since they take over
+    /// its keys while a search has matches.
+    pub fn conflicts(&self) -> Vec<String> {
+        let mut conflicts = BTreeSet::new();
+
+        for contexts in [
+            &[Context::Matches, Context::Achievements][..],
+            &[Context::Stats],
+            &[Context::Review],
+        ] {
+            let mut seen: HashMap<KeyBinding, Action> = HashMap::new();
+            let actions = contexts.iter().flat_map(|&context| self.actions(context));
+            for (action, keys) in actions {
+                for &binding in keys {
+                    match seen.get(&binding) {
+                        Some(&other) if other != action && !shadows(other, action) => {
+                            conflicts.insert(format!(
+                                "Keymap conflict: {} is bound to both {} and {}",
+                                binding,
+                                other.name(),
+                                action.name()
+                            ));
+                        }
+                        _ => {
+                            seen.insert(binding, action);
+                        }
+                    }
+                }
+            }
+        }
+
+        conflicts.into_iter().collect()
+    }
+
+    /// Key and label pairs for the Controls help. Adjacent actions with the
+    /// same label are merged, so `u` and `^r` show up as `u/^r Undo/Redo`.
+    pub fn help(
+        &self,
+        context: Context,
+        label: impl Fn(Action) -> &'static str,
+    ) -> Vec<(String, &'static str)> {
+        let mut items: Vec<(String, &'static str)> = Vec::new();
+
+        for (action, keys) in self.actions(context) {
+            if keys.is_empty() {
+                continue;
+            }
+
+            let keys = keys
+                .iter()
+                .map(|binding| binding.to_string())
+                .collect::<Vec<_>>()
+                .join("/");
+            let label = label(action);
+
+            match items.last_mut() {
+                Some((previous, previous_label)) if *previous_label == label => {
+                    previous.push('/');
+                    previous.push_str(&keys);
+                }
+                _ => items.push((keys, label)),
+            }
+        }
+
+        items
+    }
+
+    fn actions(&self, context: Context) -> impl Iterator<Item = (Action, &[KeyBinding])> {
+        self.bindings
+            .iter()
+            .filter(move |(action, _)| action.contexts().contains(&context))
+            .map(|(&action, keys)| (action, keys.as_slice()))
+    }
+}
+
+/// Whether `action` may share a key with `other`, which takes it over while
+/// there are search matches. Only sort by name is meant to: it shares `n`
+/// with next match by default, and can wait until the search is cleared.
+fn shadows(other: Action, action: Action) -> bool {
+    (other, action) == (Action::NextMatch, Action::SortByName)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn defaults_do_not_conflict() {
+        let keymap = Keymap::default();
+        assert_eq!(keymap.conflicts(), Vec::<String>::new());
+
+        for action in Action::ALL {
+            assert_eq!(
+                keymap.bindings[&action].len(),
+                action.default_keys().len(),
+                "{}",
+                action.name()
+            );
+        }
+    }
+
+    #[test]
+    fn parses_and_matches_keys() {
+        let redo = KeyBinding::parse("ctrl-r").unwrap();
+        assert!(redo.matches(&key(KeyCode::Char('r'), KeyModifiers::CONTROL)));
+        assert!(!redo.matches(&key(KeyCode::Char('r'), KeyModifiers::NONE)));
+        assert_eq!(redo.to_string(), "^r");
+
+        let bottom = KeyBinding::parse("G").unwrap();
+        assert!(bottom.matches(&key(KeyCode::Char('G'), KeyModifiers::SHIFT)));
+
+        assert_eq!(KeyBinding::parse("PgDn").unwrap().to_string(), "PgDn");
+        assert_eq!(KeyBinding::parse("F5").unwrap().code, KeyCode::F(5));
+        assert_eq!(KeyBinding::parse("-").unwrap().code, KeyCode::Char('-'));
+        assert_eq!(KeyBinding::parse("nope"), None);
+    }
+
+    #[test]
+    fn overrides_replace_defaults() {
+        let overrides = BTreeMap::from([
+            ("down".to_string(), vec!["ctrl-j".to_string()]),
+            (
+                "top".to_string(),
+                vec!["k".to_string(), "Hyper".to_string()],
+            ),
+            ("fly".to_string(), vec!["w".to_string()]),
+        ]);
+
+        let (keymap, warnings) = Keymap::with_overrides(&overrides);
+        assert_eq!(
+            warnings,
+            [
+                "Unknown action in keymap: fly",
+                "Unknown key for top: Hyper"
+            ]
+        );
+
+        let ctrl_j = key(KeyCode::Char('j'), KeyModifiers::CONTROL);
+        let j = key(KeyCode::Char('j'), KeyModifiers::NONE);
+        assert_eq!(
+            keymap.action(Context::Achievements, &ctrl_j),
+            Some(Action::Down)
+        );
+        assert_eq!(keymap.action(Context::Achievements, &j), None);
+
+        assert_eq!(
+            keymap.conflicts(),
+            ["Keymap conflict: k is bound to both up and top"]
+        );
+    }
+
+    #[test]
+    fn match_keys_conflict_with_table_keys() {
+        let overrides = BTreeMap::from([("prev-match".to_string(), vec!["v".to_string()])]);
+        let (keymap, _) = Keymap::with_overrides(&overrides);
+        assert_eq!(
+            keymap.conflicts(),
+            ["Keymap conflict: v is bound to both prev-match and visibility"]
+        );
+
+        // Next match taking over sort by name is expected, whatever the key
+        let overrides = BTreeMap::from([
+            ("next-match".to_string(), vec!["m".to_string()]),
+            ("sort-by-name".to_string(), vec!["m".to_string()]),
+        ]);
+        let (keymap, _) = Keymap::with_overrides(&overrides);
+        assert_eq!(keymap.conflicts(), Vec::<String>::new());
+    }
+
+    #[test]
+    fn help_merges_shared_labels() {
+        let keymap = Keymap::default();
+        let help = keymap.help(Context::Review, |action| action.label(Context::Review));
+
+        assert_eq!(
+            help,
+            [
+                ("j/↓".to_string(), "Down"),
+                ("k/↑".to_string(), "Up"),
+                ("Space".to_string(), "Include/Exclude"),
+                ("Enter".to_string(), "Apply"),
+                ("Esc/q".to_string(), "Back"),
+            ]
+        );
+    }
+}
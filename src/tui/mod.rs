@@ -1,8 +1,19 @@
 mod app;
 mod config;
+mod history;
+mod icon;
+mod keymap;
 mod models;
+mod picker;
 mod search;
+mod sort;
+mod stats;
 mod terminal;
+mod theme;
 mod ui;
+mod worker;
 
+pub use config::{AppConfig, ConfigFile};
+pub use models::{SortColumn, SortOrder};
+pub use sort::sort;
 pub use terminal::run;
@@ -1,4 +1,6 @@
-use ratatui::style::{Color, Style};
+use crate::steam::RESET_CONFIRMATION;
+use clap::ValueEnum;
+use ratatui::widgets::TableState;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
@@ -38,20 +40,13 @@ impl Status {
     pub fn info(message: String) -> Self {
         Self::new(message, StatusLevel::Info)
     }
-
-    pub fn style(&self) -> Style {
-        match self.level {
-            StatusLevel::Info => Style::default().fg(Color::Yellow),
-            StatusLevel::Success => Style::default().fg(Color::Green),
-            StatusLevel::Error => Style::default().fg(Color::Red),
-        }
-    }
 }
 
-#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, ValueEnum)]
 pub enum SortColumn {
     Percentage,
     Name,
+    UnlockTime,
 }
 
 impl fmt::Display for SortColumn {
@@ -59,11 +54,12 @@ impl fmt::Display for SortColumn {
         match self {
             SortColumn::Percentage => write!(f, "Percentage"),
             SortColumn::Name => write!(f, "Name"),
+            SortColumn::UnlockTime => write!(f, "Unlock Time"),
         }
     }
 }
 
-#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, ValueEnum)]
 pub enum SortOrder {
     Ascending,
     Descending,
@@ -78,11 +74,161 @@ impl fmt::Display for SortOrder {
     }
 }
 
+#[derive(Clone, Debug, Default)]
+pub struct ResetPrompt {
+    pub input: String,
+    pub include_achievements: bool,
+}
+
+impl ResetPrompt {
+    pub fn is_confirmed(&self) -> bool {
+        self.input == RESET_CONFIRMATION
+    }
+}
+
+/// An achievement listed in the review, and which way it would change.
+#[derive(Clone, Debug)]
+pub struct ReviewEntry {
+    pub name: String,
+    pub unlock: bool,
+}
+
+/// Pending achievement changes, listed for confirmation before anything is
+/// written. Unlocks come first, then clears.
+#[derive(Clone, Debug, Default)]
+pub struct Review {
+    pub entries: Vec<ReviewEntry>,
+    pub table_state: TableState,
+}
+
+impl Review {
+    pub fn current(&self) -> Option<&ReviewEntry> {
+        self.table_state
+            .selected()
+            .and_then(|index| self.entries.get(index))
+    }
+
+    pub fn next(&mut self) {
+        if !self.entries.is_empty() {
+            let index = self.table_state.selected().unwrap_or(0);
+            self.table_state
+                .select(Some((index + 1) % self.entries.len()));
+        }
+    }
+
+    pub fn previous(&mut self) {
+        if !self.entries.is_empty() {
+            let index = self.table_state.selected().unwrap_or(0);
+            self.table_state
+                .select(Some(index.checked_sub(1).unwrap_or(self.entries.len() - 1)));
+        }
+    }
+}
+
+/// What keyboard input is currently driving.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Mode {
+    Browse,
+    PickApp,
+    Search,
+    Filter,
+    EditStat,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Tab {
+    Achievements,
+    Stats,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Visibility {
+    All,
+    Visible,
+    Hidden,
+}
+
+impl Visibility {
+    pub fn next(self) -> Self {
+        match self {
+            Visibility::All => Visibility::Visible,
+            Visibility::Visible => Visibility::Hidden,
+            Visibility::Hidden => Visibility::All,
+        }
+    }
+
+    pub fn matches(self, achievement: &AchievementItem) -> bool {
+        match self {
+            Visibility::All => true,
+            Visibility::Visible => !achievement.hidden,
+            Visibility::Hidden => achievement.hidden,
+        }
+    }
+}
+
+impl fmt::Display for Visibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Visibility::All => write!(f, "All achievements"),
+            Visibility::Visible => write!(f, "Visible achievements"),
+            Visibility::Hidden => write!(f, "Hidden achievements"),
+        }
+    }
+}
+
+/// Quick filters that narrow the table to rows in one state.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum RowFilter {
+    Locked,
+    Unlocked,
+    Pending,
+    Failed,
+}
+
+impl RowFilter {
+    pub fn matches(self, achievement: &AchievementItem) -> bool {
+        match self {
+            RowFilter::Locked => !achievement.unlocked,
+            RowFilter::Unlocked => achievement.unlocked,
+            RowFilter::Pending => achievement.selected != achievement.unlocked,
+            RowFilter::Failed => achievement.status == AchievementStatus::Failed,
+        }
+    }
+}
+
+impl fmt::Display for RowFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RowFilter::Locked => write!(f, "Locked"),
+            RowFilter::Unlocked => write!(f, "Unlocked"),
+            RowFilter::Pending => write!(f, "Pending"),
+            RowFilter::Failed => write!(f, "Failed"),
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct AchievementItem {
     pub name: String,
+    pub display_name: String,
+    pub description: String,
+    pub hidden: bool,
     pub selected: bool,
     pub unlocked: bool,
-    pub percentage: f32,
+    pub unlocked_at: Option<u64>,
+    pub percentage: Option<f32>,
     pub status: AchievementStatus,
+    /// Schema file names of the unlocked and locked icons.
+    pub icon: Option<String>,
+    pub icon_gray: Option<String>,
+}
+
+impl AchievementItem {
+    pub fn title(&self) -> &str {
+        if self.display_name.is_empty() {
+            &self.name
+        } else {
+            &self.display_name
+        }
+    }
 }
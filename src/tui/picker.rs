@@ -0,0 +1,104 @@
+use super::search::fuzzy_score;
+use crate::library::{self, InstalledApp};
+use ratatui::widgets::TableState;
+use std::cmp::Reverse;
+
+pub struct Picker {
+    pub apps: Vec<InstalledApp>,
+    pub query: String,
+    pub matches: Vec<usize>,
+    pub current_index: usize,
+    pub table_state: TableState,
+    pub error: Option<String>,
+}
+
+impl Picker {
+    pub fn load() -> Self {
+        let (apps, error) = match library::installed_apps() {
+            Ok(apps) => (apps, None),
+            Err(e) => (Vec::new(), Some(e.to_string())),
+        };
+
+        let mut picker = Self {
+            apps,
+            query: String::new(),
+            matches: Vec::new(),
+            current_index: 0,
+            table_state: TableState::default(),
+            error,
+        };
+
+        picker.update_matches();
+        picker
+    }
+
+    pub fn visible_apps(&self) -> impl Iterator<Item = &InstalledApp> {
+        self.matches.iter().map(|&index| &self.apps[index])
+    }
+
+    /// Re-ranks the app list against the current query, best match first.
+    pub fn update_matches(&mut self) {
+        let query = self.query.trim();
+
+        if query.is_empty() {
+            self.matches = (0..self.apps.len()).collect();
+        } else {
+            let mut scored: Vec<(usize, i64)> = self
+                .apps
+                .iter()
+                .enumerate()
+                .filter_map(|(index, app)| {
+                    let id = app.id.to_string();
+                    [app.name.as_str(), id.as_str()]
+                        .into_iter()
+                        .filter_map(|field| fuzzy_score(field, query))
+                        .map(|found| found.score)
+                        .max()
+                        .map(|score| (index, score))
+                })
+                .collect();
+
+            scored.sort_by_key(|&(_, score)| Reverse(score));
+            self.matches = scored.into_iter().map(|(index, _)| index).collect();
+        }
+
+        self.current_index = 0;
+        self.table_state
+            .select((!self.matches.is_empty()).then_some(0));
+    }
+
+    pub fn clear(&mut self) {
+        self.query.clear();
+        self.update_matches();
+    }
+
+    pub fn next(&mut self) {
+        if !self.matches.is_empty() {
+            self.current_index = (self.current_index + 1) % self.matches.len();
+            self.table_state.select(Some(self.current_index));
+        }
+    }
+
+    pub fn previous(&mut self) {
+        if !self.matches.is_empty() {
+            if self.current_index > 0 {
+                self.current_index -= 1;
+            } else {
+                self.current_index = self.matches.len() - 1;
+            }
+            self.table_state.select(Some(self.current_index));
+        }
+    }
+
+    /// The app ID to load: a typed numeric ID wins, otherwise the highlighted game.
+    pub fn selected_id(&self) -> Option<u32> {
+        let query = self.query.trim();
+        if !query.is_empty() && query.chars().all(|c| c.is_ascii_digit()) {
+            return query.parse().ok();
+        }
+
+        self.matches
+            .get(self.current_index)
+            .map(|&index| self.apps[index].id)
+    }
+}
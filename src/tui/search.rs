@@ -1,24 +1,54 @@
-pub fn fuzzy_score(haystack: &str, needle: &str) -> Option<i64> {
-    let needle = needle.trim();
+/// How well a needle matched, and which characters of the haystack it hit.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FuzzyMatch {
+    pub score: i64,
+    /// Character (not byte) indices into the haystack, in ascending order.
+    pub positions: Vec<usize>,
+}
+
+/// Scores `needle` against `haystack`, preferring substrings, then runs of
+/// consecutive characters and matches at word boundaries.
+///
+/// Matching is smart-case: case-insensitive unless the needle contains an
+/// uppercase letter.
+pub fn fuzzy_score(haystack: &str, needle: &str) -> Option<FuzzyMatch> {
+    let needle: Vec<char> = needle.trim().chars().collect();
     if needle.is_empty() {
         return None;
     }
 
-    if haystack.contains(needle) {
-        return Some(1_000_000 - (haystack.len() as i64 - needle.len() as i64));
+    let case_sensitive = needle.iter().any(|c| c.is_uppercase());
+    let eq = |h: char, n: char| {
+        if case_sensitive {
+            h == n
+        } else {
+            h == n || h.to_lowercase().eq(n.to_lowercase())
+        }
+    };
+
+    let hay: Vec<char> = haystack.chars().collect();
+
+    if let Some(start) = hay
+        .windows(needle.len())
+        .position(|window| window.iter().zip(&needle).all(|(&h, &n)| eq(h, n)))
+    {
+        return Some(FuzzyMatch {
+            score: 1_000_000 - (hay.len() as i64 - needle.len() as i64),
+            positions: (start..start + needle.len()).collect(),
+        });
     }
 
     let mut score: i64 = 0;
-    let mut last_match_index: Option<usize> = None;
+    let mut positions = Vec::with_capacity(needle.len());
 
-    let mut hay_chars = haystack.chars().enumerate();
+    let mut hay_chars = hay.iter().copied().enumerate();
     let mut prev_char: Option<char> = None;
 
-    for needle_char in needle.chars() {
+    for &needle_char in &needle {
         let mut found: Option<(usize, char, Option<char>)> = None;
 
         for (i, h) in hay_chars.by_ref() {
-            if h == needle_char {
+            if eq(h, needle_char) {
                 found = Some((i, h, prev_char));
                 prev_char = Some(h);
                 break;
@@ -30,7 +60,7 @@ pub fn fuzzy_score(haystack: &str, needle: &str) -> Option<i64> {
 
         score += 10;
 
-        if let Some(last) = last_match_index {
+        if let Some(&last) = positions.last() {
             if i == last + 1 {
                 score += 15;
             } else {
@@ -42,14 +72,14 @@ pub fn fuzzy_score(haystack: &str, needle: &str) -> Option<i64> {
             score += 20;
         }
 
-        last_match_index = Some(i);
+        positions.push(i);
     }
 
-    if let Some(first) = last_match_index {
-        score -= first as i64;
+    if let Some(&last) = positions.last() {
+        score -= last as i64;
     }
 
-    Some(score)
+    Some(FuzzyMatch { score, positions })
 }
 
 fn is_word_boundary(prev: Option<char>, current: char) -> bool {
@@ -61,3 +91,35 @@ fn is_word_boundary(prev: Option<char>, current: char) -> bool {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefers_substrings() {
+        let substring = fuzzy_score("Win one game", "one").unwrap();
+        let scattered = fuzzy_score("Win one game", "wog").unwrap();
+
+        assert_eq!(substring.positions, [4, 5, 6]);
+        assert_eq!(scattered.positions, [0, 4, 8]);
+        assert!(substring.score > scattered.score);
+    }
+
+    #[test]
+    fn is_smart_case() {
+        assert!(fuzzy_score("ACH_WIN_ONE_GAME", "win").is_some());
+        assert!(fuzzy_score("ACH_WIN_ONE_GAME", "Win").is_none());
+        assert!(fuzzy_score("Winner", "Win").is_some());
+        assert!(fuzzy_score("winner", "Win").is_none());
+    }
+
+    #[test]
+    fn positions_count_characters_not_bytes() {
+        let found = fuzzy_score("Über Ärger", "är").unwrap();
+        assert_eq!(found.positions, [5, 6]);
+
+        assert_eq!(fuzzy_score("ab", "abc"), None);
+        assert_eq!(fuzzy_score("abc", "  "), None);
+    }
+}
@@ -0,0 +1,57 @@
+use super::models::{AchievementItem, SortColumn, SortOrder};
+use crate::steam::AchievementInfo;
+use std::cmp::Ordering;
+
+pub trait Sortable {
+    fn name(&self) -> &str;
+    fn percentage(&self) -> Option<f32>;
+    fn unlocked_at(&self) -> Option<u64>;
+}
+
+impl Sortable for AchievementItem {
+    fn name(&self) -> &str {
+        self.title()
+    }
+
+    fn percentage(&self) -> Option<f32> {
+        self.percentage
+    }
+
+    fn unlocked_at(&self) -> Option<u64> {
+        self.unlocked_at
+    }
+}
+
+impl Sortable for AchievementInfo {
+    fn name(&self) -> &str {
+        self.title()
+    }
+
+    fn percentage(&self) -> Option<f32> {
+        self.percentage
+    }
+
+    fn unlocked_at(&self) -> Option<u64> {
+        self.unlocked_at
+    }
+}
+
+pub fn sort<T: Sortable>(items: &mut [T], column: &SortColumn, order: &SortOrder) {
+    items.sort_by(|a, b| {
+        let ordering = match column {
+            // NOTE: Unknown percentages sort as rarer than any known one
+            SortColumn::Percentage => a
+                .percentage()
+                .partial_cmp(&b.percentage())
+                .unwrap_or(Ordering::Equal),
+            SortColumn::Name => a.name().cmp(b.name()),
+            SortColumn::UnlockTime => a.unlocked_at().cmp(&b.unlocked_at()),
+        };
+
+        if *order == SortOrder::Ascending {
+            ordering
+        } else {
+            ordering.reverse()
+        }
+    });
+}
@@ -0,0 +1,162 @@
+use super::models::{AchievementStatus, Status};
+use crate::steam::{ProcessResult, StatInfo, StatValue};
+use anyhow::Result;
+use ratatui::widgets::TableState;
+
+#[derive(Clone, Debug)]
+pub struct StatItem {
+    pub info: StatInfo,
+    pub pending: Option<StatValue>,
+    pub status: AchievementStatus,
+}
+
+#[derive(Default)]
+pub struct StatsView {
+    pub items: Vec<StatItem>,
+    pub current_index: usize,
+    pub table_state: TableState,
+    pub input: String,
+    pub error: Option<String>,
+}
+
+impl StatsView {
+    pub fn new(stats: Result<Vec<StatInfo>>) -> Self {
+        let (stats, error) = match stats {
+            Ok(stats) => (stats, None),
+            Err(e) => (Vec::new(), Some(e.to_string())),
+        };
+
+        let mut items: Vec<StatItem> = stats
+            .into_iter()
+            .map(|info| StatItem {
+                info,
+                pending: None,
+                status: AchievementStatus::Unchanged,
+            })
+            .collect();
+        items.sort_by(|a, b| a.info.name.cmp(&b.info.name));
+
+        let mut table_state = TableState::default();
+        table_state.select(Some(0));
+
+        Self {
+            items,
+            current_index: 0,
+            table_state,
+            input: String::new(),
+            error,
+        }
+    }
+
+    pub fn current(&self) -> Option<&StatItem> {
+        self.items.get(self.current_index)
+    }
+
+    pub fn pending_count(&self) -> usize {
+        self.items.iter().filter(|s| s.pending.is_some()).count()
+    }
+
+    pub fn next(&mut self) {
+        if !self.items.is_empty() {
+            self.current_index = (self.current_index + 1) % self.items.len();
+            self.table_state.select(Some(self.current_index));
+        }
+    }
+
+    pub fn previous(&mut self) {
+        if !self.items.is_empty() {
+            if self.current_index > 0 {
+                self.current_index -= 1;
+            } else {
+                self.current_index = self.items.len() - 1;
+            }
+            self.table_state.select(Some(self.current_index));
+        }
+    }
+
+    pub fn jump_to_top(&mut self) {
+        if !self.items.is_empty() {
+            self.current_index = 0;
+            self.table_state.select(Some(self.current_index));
+        }
+    }
+
+    pub fn jump_to_bottom(&mut self) {
+        if !self.items.is_empty() {
+            self.current_index = self.items.len() - 1;
+            self.table_state.select(Some(self.current_index));
+        }
+    }
+
+    /// Validates `input` against the highlighted stat and stages it.
+    pub fn commit_input(&mut self) -> Result<(), Status> {
+        let Some(item) = self.items.get_mut(self.current_index) else {
+            return Ok(());
+        };
+
+        let value = item
+            .info
+            .parse_value(&self.input)
+            .map_err(|e| Status::error(e.to_string()))?;
+
+        item.pending = (value != item.info.value).then_some(value);
+        item.status = AchievementStatus::Unchanged;
+        self.input.clear();
+        Ok(())
+    }
+
+    pub fn discard_pending(&mut self) {
+        if let Some(item) = self.items.get_mut(self.current_index) {
+            item.pending = None;
+        }
+    }
+
+    /// The staged values, as `(name, value)` pairs ready to send to Steam.
+    pub fn pending_changes(&self) -> Vec<(String, StatValue)> {
+        self.items
+            .iter()
+            .filter_map(|item| Some((item.info.name.clone(), item.pending?)))
+            .collect()
+    }
+
+    pub fn apply_results(&mut self, results: Result<Vec<ProcessResult>, String>) -> Status {
+        let results = match results {
+            Ok(results) => results,
+            Err(e) => {
+                for item in self.items.iter_mut().filter(|s| s.pending.is_some()) {
+                    item.status = AchievementStatus::Failed;
+                }
+                return Status::error(e);
+            }
+        };
+
+        let mut success_count = 0;
+        let mut fail_count = 0;
+
+        for result in results {
+            let Some(item) = self.items.iter_mut().find(|s| s.info.name == result.name) else {
+                continue;
+            };
+
+            if result.success {
+                item.status = AchievementStatus::Success;
+                if let Some(value) = item.pending.take() {
+                    item.info.value = value;
+                }
+                success_count += 1;
+            } else {
+                item.status = AchievementStatus::Failed;
+                fail_count += 1;
+            }
+        }
+
+        if fail_count == 0 {
+            Status::success(format!("✓ Successfully updated {} stat(s)", success_count))
+        } else {
+            Status::error(format!(
+                "⚠ Updated: {} success, {} failed",
+                success_count, fail_count
+            ))
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::steam::StatsBackend;
 use anyhow::Result;
 use crossterm::{
     event::{DisableMouseCapture, EnableMouseCapture},
@@ -40,8 +41,11 @@ impl Drop for TerminalGuard {
     }
 }
 
-pub fn run(initial_app_id: Option<u32>) -> Result<()> {
+pub fn run(
+    new_backend: impl Fn() -> Box<dyn StatsBackend + Send> + 'static,
+    initial_app_id: Option<u32>,
+) -> Result<()> {
     let mut terminal = TerminalGuard::new()?;
-    super::ui::run(terminal.inner_mut(), initial_app_id)?;
+    super::ui::run(terminal.inner_mut(), new_backend, initial_app_id)?;
     Ok(())
 }
@@ -0,0 +1,372 @@
+use super::config::AppConfig;
+use super::models::StatusLevel;
+use ratatui::style::{Color, Modifier, Style};
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fmt;
+use std::str::FromStr;
+
+/// Global unlock percentages at or below which an achievement counts as
+/// each rarity. Anything above `uncommon` is common.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RarityBounds {
+    pub legendary: f32,
+    pub epic: f32,
+    pub rare: f32,
+    pub uncommon: f32,
+}
+
+impl Default for RarityBounds {
+    fn default() -> Self {
+        Self {
+            legendary: 1.0,
+            epic: 10.0,
+            rare: 25.0,
+            uncommon: 50.0,
+        }
+    }
+}
+
+impl RarityBounds {
+    pub fn tier(&self, percentage: f32) -> Rarity {
+        if percentage <= self.legendary {
+            Rarity::Legendary
+        } else if percentage <= self.epic {
+            Rarity::Epic
+        } else if percentage <= self.rare {
+            Rarity::Rare
+        } else if percentage <= self.uncommon {
+            Rarity::Uncommon
+        } else {
+            Rarity::Common
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        let bounds = [self.legendary, self.epic, self.rare, self.uncommon];
+        bounds.iter().all(|bound| bound.is_finite()) && bounds.is_sorted()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Rarity {
+    Legendary,
+    Epic,
+    Rare,
+    Uncommon,
+    Common,
+}
+
+impl fmt::Display for Rarity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rarity::Legendary => write!(f, "Legendary"),
+            Rarity::Epic => write!(f, "Epic"),
+            Rarity::Rare => write!(f, "Rare"),
+            Rarity::Uncommon => write!(f, "Uncommon"),
+            Rarity::Common => write!(f, "Common"),
+        }
+    }
+}
+
+/// Colors by role rather than by hue, so the same draw code works on any
+/// background. `Color::Reset` leaves the terminal's own color alone.
+#[derive(Clone, Debug)]
+pub struct Theme {
+    /// Headings, the title bar and the busy spinner.
+    pub accent: Color,
+    pub info: Color,
+    pub success: Color,
+    pub error: Color,
+    /// Secondary text such as descriptions and timestamps.
+    pub muted: Color,
+    /// Text that should barely be there, like API names.
+    pub faint: Color,
+    /// Table titles and hidden achievements.
+    pub emphasis: Color,
+    /// Background of the highlighted row, or `Reset` for reverse video.
+    pub highlight: Color,
+    pub legendary: Color,
+    pub epic: Color,
+    pub rare: Color,
+    pub uncommon: Color,
+    pub common: Color,
+    pub bounds: RarityBounds,
+}
+
+pub const THEMES: [&str; 4] = ["dark", "light", "high-contrast", "monochrome"];
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Self {
+            accent: Color::Cyan,
+            info: Color::Yellow,
+            success: Color::Green,
+            error: Color::Red,
+            muted: Color::Gray,
+            faint: Color::DarkGray,
+            emphasis: Color::Magenta,
+            highlight: Color::Rgb(0x18, 0x18, 0x18),
+            legendary: Color::Rgb(255, 128, 0),
+            epic: Color::Rgb(163, 53, 238),
+            rare: Color::Rgb(0, 112, 221),
+            uncommon: Color::Rgb(30, 255, 0),
+            common: Color::Rgb(255, 255, 255),
+            bounds: RarityBounds::default(),
+        }
+    }
+
+    pub fn light() -> Self {
+        Self {
+            accent: Color::Rgb(0, 95, 135),
+            info: Color::Rgb(135, 95, 0),
+            success: Color::Rgb(0, 125, 0),
+            error: Color::Rgb(190, 0, 0),
+            muted: Color::Rgb(88, 88, 88),
+            faint: Color::Rgb(138, 138, 138),
+            emphasis: Color::Rgb(135, 0, 135),
+            highlight: Color::Rgb(0xe4, 0xe4, 0xe4),
+            legendary: Color::Rgb(200, 90, 0),
+            epic: Color::Rgb(120, 30, 190),
+            rare: Color::Rgb(0, 85, 175),
+            uncommon: Color::Rgb(20, 130, 0),
+            common: Color::Rgb(0, 0, 0),
+            bounds: RarityBounds::default(),
+        }
+    }
+
+    /// Bright ANSI colors only, so it also works without truecolor.
+    pub fn high_contrast() -> Self {
+        Self {
+            accent: Color::LightCyan,
+            info: Color::LightYellow,
+            success: Color::LightGreen,
+            error: Color::LightRed,
+            muted: Color::White,
+            faint: Color::Gray,
+            emphasis: Color::LightMagenta,
+            highlight: Color::Reset,
+            legendary: Color::LightRed,
+            epic: Color::LightMagenta,
+            rare: Color::LightBlue,
+            uncommon: Color::LightGreen,
+            common: Color::White,
+            bounds: RarityBounds::default(),
+        }
+    }
+
+    /// No colors at all; emphasis comes from bold, italic and reverse video.
+    pub fn monochrome() -> Self {
+        Self {
+            accent: Color::Reset,
+            info: Color::Reset,
+            success: Color::Reset,
+            error: Color::Reset,
+            muted: Color::Reset,
+            faint: Color::Reset,
+            emphasis: Color::Reset,
+            highlight: Color::Reset,
+            legendary: Color::Reset,
+            epic: Color::Reset,
+            rare: Color::Reset,
+            uncommon: Color::Reset,
+            common: Color::Reset,
+            bounds: RarityBounds::default(),
+        }
+    }
+
+    pub fn named(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "high-contrast" => Some(Self::high_contrast()),
+            "monochrome" => Some(Self::monochrome()),
+            _ => None,
+        }
+    }
+
+    /// The configured theme, or monochrome if none is configured and the
+    /// terminal can't or shouldn't show the default palette.
+    pub fn load(config: &AppConfig) -> (Self, Vec<String>) {
+        Self::from_config(config, prefers_monochrome())
+    }
+
+    fn from_config(config: &AppConfig, monochrome: bool) -> (Self, Vec<String>) {
+        let mut warnings = Vec::new();
+
+        let mut theme = match config.theme.as_deref() {
+            Some(name) => Self::named(name).unwrap_or_else(|| {
+                warnings.push(format!(
+                    "Unknown theme: {} (expected one of {})",
+                    name,
+                    THEMES.join(", ")
+                ));
+                Self::default()
+            }),
+            None if monochrome => Self::monochrome(),
+            None => Self::default(),
+        };
+
+        for (role, value) in &config.colors {
+            let Some(slot) = theme.role_mut(role) else {
+                warnings.push(format!("Unknown theme color: {}", role));
+                continue;
+            };
+            match Color::from_str(value) {
+                Ok(color) => *slot = color,
+                Err(_) => warnings.push(format!("Invalid color for {}: {}", role, value)),
+            }
+        }
+
+        if config.rarity.is_valid() {
+            theme.bounds = config.rarity.clone();
+        } else {
+            warnings.push("Rarity thresholds must increase from legendary to uncommon".to_string());
+        }
+
+        (theme, warnings)
+    }
+
+    fn role_mut(&mut self, role: &str) -> Option<&mut Color> {
+        Some(match role {
+            "accent" => &mut self.accent,
+            "info" => &mut self.info,
+            "success" => &mut self.success,
+            "error" => &mut self.error,
+            "muted" => &mut self.muted,
+            "faint" => &mut self.faint,
+            "emphasis" => &mut self.emphasis,
+            "highlight" => &mut self.highlight,
+            "legendary" => &mut self.legendary,
+            "epic" => &mut self.epic,
+            "rare" => &mut self.rare,
+            "uncommon" => &mut self.uncommon,
+            "common" => &mut self.common,
+            _ => return None,
+        })
+    }
+
+    pub fn heading(&self) -> Style {
+        Style::default()
+            .fg(self.accent)
+            .add_modifier(Modifier::BOLD)
+    }
+
+    pub fn title(&self) -> Style {
+        Style::default()
+            .fg(self.emphasis)
+            .add_modifier(Modifier::BOLD)
+    }
+
+    pub fn highlight(&self) -> Style {
+        let style = Style::default().add_modifier(Modifier::BOLD);
+        if self.highlight == Color::Reset {
+            style.add_modifier(Modifier::REVERSED)
+        } else {
+            style.bg(self.highlight)
+        }
+    }
+
+    pub fn status(&self, level: &StatusLevel) -> Style {
+        Style::default().fg(match level {
+            StatusLevel::Info => self.info,
+            StatusLevel::Success => self.success,
+            StatusLevel::Error => self.error,
+        })
+    }
+
+    pub fn rarity_color(&self, rarity: Rarity) -> Color {
+        match rarity {
+            Rarity::Legendary => self.legendary,
+            Rarity::Epic => self.epic,
+            Rarity::Rare => self.rare,
+            Rarity::Uncommon => self.uncommon,
+            Rarity::Common => self.common,
+        }
+    }
+
+    /// How a rarity is drawn, with the two rarest also in bold.
+    pub fn rarity(&self, rarity: Rarity) -> Style {
+        let style = Style::default().fg(self.rarity_color(rarity));
+        match rarity {
+            Rarity::Legendary | Rarity::Epic => style.add_modifier(Modifier::BOLD),
+            Rarity::Rare | Rarity::Uncommon | Rarity::Common => style,
+        }
+    }
+}
+
+/// Whether colors should be off by default: `NO_COLOR` is set, or the
+/// terminal doesn't advertise 24-bit color.
+pub fn prefers_monochrome() -> bool {
+    let no_color = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+    let truecolor = env::var("COLORTERM").is_ok_and(|value| value == "truecolor" || value == "24bit")
+        // NOTE: Windows Terminal supports truecolor without setting COLORTERM
+        || env::var_os("WT_SESSION").is_some();
+    no_color || !truecolor
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn tiers_follow_the_bounds() {
+        let bounds = RarityBounds::default();
+        assert_eq!(bounds.tier(0.4), Rarity::Legendary);
+        assert_eq!(bounds.tier(1.0), Rarity::Legendary);
+        assert_eq!(bounds.tier(3.5), Rarity::Epic);
+        assert_eq!(bounds.tier(42.0), Rarity::Uncommon);
+        assert_eq!(bounds.tier(80.0), Rarity::Common);
+    }
+
+    #[test]
+    fn configured_theme_beats_monochrome() {
+        let mut config = AppConfig::default();
+        let (theme, _) = Theme::from_config(&config, true);
+        assert_eq!(theme.accent, Color::Reset);
+
+        config.theme = Some("light".to_string());
+        let (theme, warnings) = Theme::from_config(&config, true);
+        assert_eq!(theme.common, Color::Rgb(0, 0, 0));
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn applies_overrides_and_reports_bad_ones() {
+        let config = AppConfig {
+            colors: BTreeMap::from([
+                ("accent".to_string(), "#ff8000".to_string()),
+                ("rare".to_string(), "blue".to_string()),
+                ("error".to_string(), "angry".to_string()),
+                ("border".to_string(), "red".to_string()),
+            ]),
+            rarity: RarityBounds {
+                epic: 0.5,
+                ..RarityBounds::default()
+            },
+            ..AppConfig::default()
+        };
+
+        let (theme, warnings) = Theme::from_config(&config, false);
+        assert_eq!(theme.accent, Color::Rgb(255, 128, 0));
+        assert_eq!(theme.rare, Color::Blue);
+        assert_eq!(theme.error, Color::Red);
+        assert_eq!(theme.bounds, RarityBounds::default());
+        assert_eq!(
+            warnings,
+            [
+                "Unknown theme color: border",
+                "Invalid color for error: angry",
+                "Rarity thresholds must increase from legendary to uncommon",
+            ]
+        );
+    }
+}
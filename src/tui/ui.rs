@@ -1,74 +1,286 @@
 use super::app::App;
-use super::models::{AchievementStatus, SortColumn, SortOrder, Status};
-use crate::steam::{self};
+use super::config::{AppConfig, ConfigFile};
+use super::icon::{Graphics, ICON_COLS, ICON_ROWS, IconBlocks, Placement, Protocol};
+use super::keymap::{Action, Context, Keymap};
+use super::models::{
+    AchievementStatus, Mode, ResetPrompt, Review, RowFilter, SortColumn, SortOrder, Status, Tab,
+};
+use super::picker::Picker;
+use super::search::fuzzy_score;
+use super::stats::StatsView;
+use super::theme::Theme;
+use super::worker::{Job, Loaded, Outcome, Task, Worker};
+use crate::snapshot::{self, Snapshot};
+use crate::steam::{RESET_CONFIRMATION, StatsBackend};
+use crate::time;
 use anyhow::Result;
-use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::event::{
+    self, Event, KeyCode, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
 use ratatui::{
     Frame, Terminal,
     backend::Backend,
-    layout::{Constraint, Direction, Layout},
-    style::{Color, Modifier, Style},
+    layout::{Constraint, Direction, Flex, Layout, Margin, Position, Rect, Size},
+    style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Cell, Paragraph, Row, Table},
+    widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table, Wrap},
 };
+use std::io;
+use std::mem;
+use std::time::Duration;
 
 const STATUS_SEARCH_HINT: &str = "Search: Typing...";
-const STATUS_APP_ID_HINT: &str = "App ID: Typing...";
+const STATUS_APP_ID_HINT: &str = "Game: Type a name or App ID...";
+const STATUS_FILTER_HINT: &str = "Filter: Typing...";
+const STATUS_STAT_HINT: &str = "Stat: Typing...";
+const STATUS_NO_CHANGES: &str = "No achievement changes to apply";
+const HIDDEN_DESCRIPTION: &str = "Hidden achievement";
+const UNKNOWN_PERCENTAGE: &str = "—";
+const TICK: Duration = Duration::from_millis(100);
+
+/// Widths of the achievements table's Done, Global, Unlocked, Achievement
+/// Name, Description and API Name columns.
+const ACHIEVEMENT_COLUMNS: [Constraint; 6] = [
+    Constraint::Length(6),
+    Constraint::Length(8),
+    Constraint::Length(16),
+    Constraint::Fill(2),
+    Constraint::Fill(3),
+    Constraint::Fill(2),
+];
+const COLUMN_DONE: usize = 0;
+const COLUMN_SPACING: u16 = 1;
+
+/// Below this width the details panel covers the table instead of sitting
+/// beside it.
+const DETAILS_SPLIT_WIDTH: u16 = 120;
+const DETAILS_WIDTH: u16 = 44;
+/// Rows of text the details panel keeps below an icon.
+const DETAILS_MIN_TEXT_ROWS: u16 = 8;
 
 fn status_no_match(query: &str) -> Status {
     Status::info(format!("No match for: {}", query))
 }
 
-const COLOR_LEGENDARY: Color = Color::Rgb(255, 128, 0);
-const BOUND_LEGENDARY: f32 = 1.0;
-const COLOR_EPIC: Color = Color::Rgb(163, 53, 238);
-const BOUND_EPIC: f32 = 10.0;
-const COLOR_RARE: Color = Color::Rgb(0, 112, 221);
-const BOUND_RARE: f32 = 25.0;
-const COLOR_UNCOMMON: Color = Color::Rgb(30, 255, 0);
-const BOUND_UNCOMMON: f32 = 50.0;
-const COLOR_COMMON: Color = Color::Rgb(255, 255, 255);
-
-pub fn run<B: Backend>(terminal: &mut Terminal<B>, initial_app_id: Option<u32>) -> Result<()>
+fn filter_status(app: &App) -> Option<Status> {
+    if app.view.is_empty() && !app.filter_query.trim().is_empty() {
+        Some(status_no_match(&app.filter_query))
+    } else {
+        None
+    }
+}
+
+pub fn run<B: Backend + io::Write>(
+    terminal: &mut Terminal<B>,
+    new_backend: impl Fn() -> Box<dyn StatsBackend + Send> + 'static,
+    initial_app_id: Option<u32>,
+) -> Result<()>
 where
     <B as Backend>::Error: Send + Sync + 'static,
 {
-    let mut app_opt = initial_app_id.and_then(|id| {
-        steam::get_achievements(id)
-            .ok()
-            .map(|achievements| App::new(achievements, id))
-    });
+    let mut worker = Worker::spawn(new_backend);
+    let mut app_opt: Option<App> = None;
+
+    let (mut config, config_error) = ConfigFile::load();
+    let (settings, mut warnings) = Settings::load(&config.config);
+    warnings.splice(0..0, config_error);
+    let keymap = &settings.keymap;
+    let mut warning = config_warning(&warnings);
+    let mut graphics = Graphics::new(settings.icons);
 
-    let mut app_id_input = String::new();
+    let mut picker = Picker::load();
     let mut status: Option<Status> = None;
-    let mut editing_app_id = app_opt.is_none();
-    let mut editing_search = false;
+    let mut mode = match initial_app_id {
+        Some(id) => {
+            worker.submit(Job::Load(id));
+            Mode::Browse
+        }
+        None => {
+            status = warning.take();
+            Mode::PickApp
+        }
+    };
 
     loop {
+        if let Some(outcome) = worker.poll() {
+            match outcome {
+                Outcome::Loaded(Ok(loaded)) => {
+                    if let Some(app) = app_opt.take() {
+                        config = app.config;
+                    }
+                    let mut app = open_app(loaded, mem::take(&mut config));
+                    app.status = warning.take();
+                    app_opt = Some(app);
+                    mode = Mode::Browse;
+                    picker.clear();
+                    status = None;
+                }
+                Outcome::Loaded(Err(e)) => {
+                    mode = Mode::PickApp;
+                    status = Some(Status::error(e.to_string()));
+                }
+                Outcome::Applied { unlocked, cleared } => {
+                    if let Some(app) = app_opt.as_mut() {
+                        app.apply_results(unlocked, cleared);
+                    }
+                }
+                Outcome::Percentages { id, percentages } => {
+                    if let Some(app) = app_opt.as_mut()
+                        && app.app_id == id
+                    {
+                        app.update_percentages(&percentages);
+                    }
+                }
+                Outcome::StatsApplied(results) => {
+                    if let Some(app) = app_opt.as_mut() {
+                        app.status = Some(app.stats.apply_results(results));
+                    }
+                }
+                Outcome::Reset {
+                    include_achievements,
+                    result,
+                } => {
+                    if let Some(app) = app_opt.as_mut() {
+                        match result {
+                            Ok(loaded) => {
+                                let tab = app.tab;
+                                *app = open_app(loaded, mem::take(&mut app.config));
+                                app.tab = tab;
+                                app.status = Some(Status::success(
+                                    if include_achievements {
+                                        "✓ Reset all stats and achievements"
+                                    } else {
+                                        "✓ Reset all stats"
+                                    }
+                                    .to_string(),
+                                ));
+                            }
+                            Err(e) => {
+                                app.status = Some(Status::error(e.to_string()));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut placement = None;
         terminal.draw(|f| {
-            draw(
+            placement = draw(
                 f,
                 app_opt.as_mut(),
-                &app_id_input,
+                &mut picker,
                 status.as_ref(),
-                editing_app_id,
-                editing_search,
+                mode,
+                worker.task(),
+                &settings,
             )
         })?;
+        if graphics.sync(terminal.backend_mut(), placement)? {
+            terminal.clear()?;
+        }
+
+        if !event::poll(TICK)? {
+            continue;
+        }
 
-        if let Event::Key(key) = event::read()?
+        let event = event::read()?;
+
+        if let Event::Mouse(mouse) = event {
+            if worker.task().is_none()
+                && mode == Mode::Browse
+                && let Some(app) = app_opt.as_mut()
+                && app.reset_prompt.is_none()
+                && app.review.is_none()
+            {
+                handle_mouse(app, mouse);
+            }
+            continue;
+        }
+
+        if let Event::Key(key) = event
             && key.kind == KeyEventKind::Press
         {
-            if editing_search {
+            if worker.task().is_some() {
+                if key.code == KeyCode::Esc
+                    && let Some(task) = worker.cancel()
+                {
+                    if app_opt.is_none() {
+                        mode = Mode::PickApp;
+                    }
+                    status = Some(Status::info(format!("Cancelled: {}", task.label)));
+                }
+            } else if let Some(app) = app_opt.as_mut()
+                && let Some(prompt) = app.reset_prompt.as_mut()
+            {
+                match key.code {
+                    KeyCode::Esc => {
+                        app.reset_prompt = None;
+                    }
+                    KeyCode::Tab => {
+                        prompt.include_achievements = !prompt.include_achievements;
+                    }
+                    KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        prompt.input.clear();
+                    }
+                    KeyCode::Char(c)
+                        if !key.modifiers.contains(KeyModifiers::CONTROL)
+                            && prompt.input.len() < RESET_CONFIRMATION.len() =>
+                    {
+                        prompt.input.push(c);
+                    }
+                    KeyCode::Backspace => {
+                        prompt.input.pop();
+                    }
+                    KeyCode::Enter if prompt.is_confirmed() => {
+                        worker.submit(Job::Reset {
+                            id: app.app_id,
+                            include_achievements: prompt.include_achievements,
+                        });
+                        app.reset_prompt = None;
+                    }
+                    _ => {}
+                }
+            } else if let Some(app) = app_opt.as_mut()
+                && let Some(review) = app.review.as_mut()
+            {
+                match keymap.action(Context::Review, &key) {
+                    Some(Action::Back | Action::Quit) => {
+                        app.review = None;
+                    }
+                    Some(Action::Down) => {
+                        review.next();
+                    }
+                    Some(Action::Up) => {
+                        review.previous();
+                    }
+                    Some(Action::Toggle) => {
+                        app.toggle_review_entry();
+                    }
+                    Some(Action::Confirm) => {
+                        app.review = None;
+                        let changes = app.pending_changes();
+                        if changes.is_empty() {
+                            app.status = Some(Status::info(STATUS_NO_CHANGES.to_string()));
+                        } else {
+                            worker.submit(Job::Apply {
+                                id: app.app_id,
+                                changes,
+                            });
+                        }
+                    }
+                    _ => {}
+                }
+            } else if mode == Mode::Search {
                 if let Some(app) = app_opt.as_mut() {
                     match key.code {
                         KeyCode::Char('q') | KeyCode::Esc => {
-                            editing_search = false;
-                            app.search_query.clear();
+                            mode = Mode::Browse;
+                            app.clear_search();
                             status = None;
                         }
                         KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                            app.search_query.clear();
+                            app.clear_search();
                             status = None;
                         }
                         KeyCode::Char(c)
@@ -77,7 +289,7 @@ where
                                 && !key.modifiers.contains(KeyModifiers::SUPER) =>
                         {
                             app.search_query.push(c);
-                            if app.search_first_match() {
+                            if app.update_search() {
                                 status = None;
                             } else {
                                 status = Some(status_no_match(&app.search_query));
@@ -85,7 +297,7 @@ where
                         }
                         KeyCode::Backspace => {
                             app.search_query.pop();
-                            status = if app.search_query.is_empty() || app.search_first_match() {
+                            status = if app.update_search() || app.search_query.is_empty() {
                                 None
                             } else {
                                 Some(status_no_match(&app.search_query))
@@ -93,131 +305,336 @@ where
                         }
 
                         KeyCode::Enter => {
-                            editing_search = false;
+                            mode = Mode::Browse;
+                            status = None;
+                        }
+                        _ => {}
+                    }
+                }
+            } else if mode == Mode::Filter {
+                if let Some(app) = app_opt.as_mut() {
+                    match key.code {
+                        KeyCode::Esc => {
+                            mode = Mode::Browse;
+                            app.filter_query.clear();
+                            app.refresh_view();
+                            status = None;
+                        }
+                        KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            app.filter_query.clear();
+                            app.update_filter();
+                            status = None;
+                        }
+                        KeyCode::Char(c)
+                            if !key.modifiers.contains(KeyModifiers::CONTROL)
+                                && !key.modifiers.contains(KeyModifiers::ALT)
+                                && !key.modifiers.contains(KeyModifiers::SUPER) =>
+                        {
+                            app.filter_query.push(c);
+                            app.update_filter();
+                            status = filter_status(app);
+                        }
+                        KeyCode::Backspace => {
+                            app.filter_query.pop();
+                            app.update_filter();
+                            status = filter_status(app);
+                        }
+                        KeyCode::Down => {
+                            app.next();
+                        }
+                        KeyCode::Up => {
+                            app.previous();
+                        }
+                        KeyCode::Enter => {
+                            mode = Mode::Browse;
+                            status = None;
+                        }
+                        _ => {}
+                    }
+                }
+            } else if mode == Mode::EditStat {
+                if let Some(app) = app_opt.as_mut() {
+                    match key.code {
+                        KeyCode::Char('q') | KeyCode::Esc => {
+                            mode = Mode::Browse;
+                            app.stats.input.clear();
+                            status = None;
+                        }
+                        KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            app.stats.input.clear();
                             status = None;
                         }
+                        KeyCode::Char(c)
+                            if (c.is_ascii_digit() || matches!(c, '-' | '.' | 'e' | 'E'))
+                                && app.stats.input.len() < 32 =>
+                        {
+                            app.stats.input.push(c);
+                            status = None;
+                        }
+                        KeyCode::Backspace => {
+                            app.stats.input.pop();
+                            status = None;
+                        }
+                        KeyCode::Enter => match app.stats.commit_input() {
+                            Ok(()) => {
+                                mode = Mode::Browse;
+                                status = None;
+                            }
+                            Err(e) => status = Some(e),
+                        },
                         _ => {}
                     }
                 }
-            } else if editing_app_id {
+            } else if mode == Mode::PickApp {
                 match key.code {
-                    KeyCode::Char('q') | KeyCode::Esc => {
+                    KeyCode::Esc => {
                         if app_opt.is_none() {
                             return Ok(());
                         } else {
-                            editing_app_id = false;
-                            app_id_input.clear();
+                            mode = Mode::Browse;
+                            picker.clear();
                             status = None;
                         }
                     }
                     KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                        app_id_input.clear();
+                        picker.clear();
                         status = None;
                     }
-                    KeyCode::Char(c) if c.is_ascii_digit() => {
-                        if app_id_input.len() < 10 {
-                            app_id_input.push(c);
-                            status = None;
-                        }
+                    KeyCode::Down => {
+                        picker.next();
+                    }
+                    KeyCode::Char('n') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        picker.next();
+                    }
+                    KeyCode::Up => {
+                        picker.previous();
+                    }
+                    KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        picker.previous();
+                    }
+                    KeyCode::Char(c)
+                        if !key.modifiers.contains(KeyModifiers::CONTROL)
+                            && !key.modifiers.contains(KeyModifiers::ALT)
+                            && !key.modifiers.contains(KeyModifiers::SUPER) =>
+                    {
+                        picker.query.push(c);
+                        picker.update_matches();
+                        status = None;
                     }
                     KeyCode::Backspace => {
-                        app_id_input.pop();
+                        picker.query.pop();
+                        picker.update_matches();
                         status = None;
                     }
-                    KeyCode::Enter => {
-                        if app_id_input.is_empty() {
-                            if app_opt.is_none() {
-                                return Ok(());
-                            } else {
-                                editing_app_id = false;
-                                app_id_input.clear();
+                    KeyCode::Enter => match picker.selected_id() {
+                        Some(id) => {
+                            worker.submit(Job::Load(id));
+                            status = None;
+                        }
+                        None if picker.query.trim().is_empty() => {
+                            if app_opt.is_some() {
+                                mode = Mode::Browse;
                                 status = None;
+                            } else {
+                                status = Some(Status::info(STATUS_APP_ID_HINT.to_string()));
                             }
+                        }
+                        None => {
+                            status =
+                                Some(Status::error(format!("No game matches: {}", picker.query)));
+                        }
+                    },
+                    _ => {}
+                }
+            } else if let Some(app) = app_opt.as_mut()
+                && app.tab == Tab::Stats
+            {
+                match keymap.action(Context::Stats, &key) {
+                    Some(Action::Back | Action::Quit) => {
+                        return Ok(());
+                    }
+                    Some(Action::SwitchApp) => {
+                        mode = Mode::PickApp;
+                        picker.clear();
+                        status = None;
+                    }
+                    Some(Action::SwitchTab) => {
+                        app.switch_tab();
+                    }
+                    Some(Action::Down) => {
+                        app.stats.next();
+                    }
+                    Some(Action::Up) => {
+                        app.stats.previous();
+                    }
+                    Some(Action::Top) => {
+                        app.stats.jump_to_top();
+                    }
+                    Some(Action::Bottom) => {
+                        app.stats.jump_to_bottom();
+                    }
+                    Some(Action::EditStat) => match app.stats.current() {
+                        Some(item) if item.info.is_editable() => {
+                            app.stats.input = item.pending.unwrap_or(item.info.value).to_string();
+                            mode = Mode::EditStat;
+                            status = Some(Status::info(STATUS_STAT_HINT.to_string()));
+                        }
+                        Some(item) => {
+                            app.status = Some(Status::error(format!(
+                                "{} is an average rate and can't be edited",
+                                item.info.name
+                            )));
+                        }
+                        None => {}
+                    },
+                    Some(Action::DiscardStat) => {
+                        app.stats.discard_pending();
+                    }
+                    Some(Action::ResetAll) => {
+                        app.reset_prompt = Some(ResetPrompt::default());
+                    }
+                    Some(Action::Confirm) => {
+                        let changes = app.stats.pending_changes();
+                        if changes.is_empty() {
+                            app.status = Some(Status::info("No stat changes to apply".to_string()));
                         } else {
-                            match app_id_input.parse::<u32>() {
-                                Ok(id) => match steam::get_achievements(id) {
-                                    Ok(achievements) => {
-                                        app_opt = Some(App::new(achievements, id));
-                                        editing_app_id = false;
-                                        app_id_input.clear();
-                                        status = None;
-                                    }
-                                    Err(e) => {
-                                        status = Some(Status::error(e.to_string()));
-                                        app_id_input.clear();
-                                    }
-                                },
-                                Err(_) => {
-                                    status = Some(Status::error(format!(
-                                        "Invalid App ID: {}",
-                                        app_id_input
-                                    )));
-                                    app_id_input.clear();
-                                }
-                            }
+                            worker.submit(Job::ApplyStats {
+                                id: app.app_id,
+                                changes,
+                            });
                         }
                     }
                     _ => {}
                 }
             } else if let Some(app) = app_opt.as_mut() {
-                match key.code {
-                    KeyCode::Char('q') | KeyCode::Esc => {
+                let action = app
+                    .has_search_matches()
+                    .then(|| keymap.action(Context::Matches, &key))
+                    .flatten()
+                    .or_else(|| keymap.action(Context::Achievements, &key));
+
+                match action {
+                    Some(Action::Back) if app.has_search_matches() => {
+                        app.clear_search();
+                        app.status = Some(Status::info("Search cleared".to_string()));
+                    }
+                    Some(Action::Back) if app.is_filtered() => {
+                        app.clear_filter();
+                        app.status = Some(Status::info("Filter cleared".to_string()));
+                    }
+                    Some(Action::Back) if app.details_popup.is_some() => {
+                        app.toggle_details();
+                    }
+                    Some(Action::Back | Action::Quit) => {
                         return Ok(());
                     }
-                    KeyCode::Char('i') => {
-                        editing_app_id = true;
-                        app_id_input.clear();
+                    Some(Action::SwitchTab) => {
+                        app.switch_tab();
+                    }
+                    Some(Action::SwitchApp) => {
+                        mode = Mode::PickApp;
+                        picker.clear();
                         status = None;
                     }
-                    KeyCode::Char('/') => {
-                        editing_search = true;
-                        app.search_query.clear();
+                    Some(Action::NextMatch) => {
+                        app.next_match();
+                    }
+                    Some(Action::PrevMatch) => {
+                        app.previous_match();
+                    }
+                    Some(Action::Search) => {
+                        mode = Mode::Search;
+                        app.clear_search();
                         status = Some(Status::info(STATUS_SEARCH_HINT.to_string()));
                     }
-                    KeyCode::Down | KeyCode::Char('j') => {
+                    Some(Action::Filter) => {
+                        mode = Mode::Filter;
+                        status = Some(Status::info(STATUS_FILTER_HINT.to_string()));
+                    }
+                    Some(Action::ShowLocked) => {
+                        app.toggle_row_filter(RowFilter::Locked);
+                    }
+                    Some(Action::ShowUnlocked) => {
+                        app.toggle_row_filter(RowFilter::Unlocked);
+                    }
+                    Some(Action::ShowPending) => {
+                        app.toggle_row_filter(RowFilter::Pending);
+                    }
+                    Some(Action::ShowFailed) => {
+                        app.toggle_row_filter(RowFilter::Failed);
+                    }
+                    Some(Action::Down) => {
                         app.next();
                     }
-                    KeyCode::Up | KeyCode::Char('k') => {
+                    Some(Action::Up) => {
                         app.previous();
                     }
-                    KeyCode::Char('g') => {
+                    Some(Action::Top) => {
                         app.jump_to_top();
                     }
-                    KeyCode::Char('G') => {
+                    Some(Action::Bottom) => {
                         app.jump_to_bottom();
                     }
-                    KeyCode::PageUp => {
+                    Some(Action::PageUp) => {
                         app.page_up();
                     }
-                    KeyCode::PageDown => {
+                    Some(Action::PageDown) => {
                         app.page_down();
                     }
-                    KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                        app.page_up();
-                    }
-                    KeyCode::Char('n') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                        app.page_down();
-                    }
-                    KeyCode::Char(' ') => {
+                    Some(Action::Toggle) => {
                         app.toggle_selection();
                     }
-                    KeyCode::Char('a') => {
+                    Some(Action::SelectAll) => {
                         app.select_all();
                     }
-                    KeyCode::Char('d') => {
+                    Some(Action::DeselectAll) => {
                         app.deselect_all();
                     }
-                    KeyCode::Char('p') => {
+                    Some(Action::Undo) => {
+                        app.undo();
+                    }
+                    Some(Action::Redo) => {
+                        app.redo();
+                    }
+                    Some(Action::RevertApply) => {
+                        app.revert_last_apply();
+                    }
+                    Some(Action::SortByPercentage) => {
                         app.set_sort_column(SortColumn::Percentage);
                     }
-                    KeyCode::Char('n') => {
+                    Some(Action::SortByName) => {
                         app.set_sort_column(SortColumn::Name);
                     }
-                    KeyCode::Char('o') => {
+                    Some(Action::SortByUnlockTime) => {
+                        app.set_sort_column(SortColumn::UnlockTime);
+                    }
+                    Some(Action::SortOrder) => {
                         app.toggle_sort_order();
                     }
-                    KeyCode::Enter => {
-                        app.process_changes();
+                    Some(Action::Visibility) => {
+                        app.cycle_visibility();
+                    }
+                    Some(Action::Spoilers) => {
+                        app.toggle_spoilers();
+                    }
+                    Some(Action::Details) => {
+                        app.toggle_details();
+                    }
+                    Some(Action::ResetAll) => {
+                        app.reset_prompt = Some(ResetPrompt::default());
+                    }
+                    Some(Action::SaveSnapshot) => {
+                        app.status = Some(save_snapshot(app));
+                    }
+                    Some(Action::LoadSnapshot) => {
+                        app.status = Some(load_snapshot(app));
+                    }
+                    Some(Action::Confirm) => {
+                        app.status = if app.open_review() {
+                            None
+                        } else {
+                            Some(Status::info(STATUS_NO_CHANGES.to_string()))
+                        };
                     }
                     _ => {}
                 }
@@ -226,48 +643,248 @@ where
     }
 }
 
+/// What the config file says about how the TUI looks and responds, read
+/// once at startup.
+struct Settings {
+    keymap: Keymap,
+    theme: Theme,
+    /// How icons are drawn, if at all.
+    icons: Option<Protocol>,
+}
+
+impl Settings {
+    fn load(config: &AppConfig) -> (Self, Vec<String>) {
+        let (keymap, mut warnings) = Keymap::with_overrides(&config.keys);
+        warnings.extend(keymap.conflicts());
+        let (theme, theme_warnings) = Theme::load(config);
+        warnings.extend(theme_warnings);
+        let (icons, icon_warnings) = Protocol::load(config);
+        warnings.extend(icon_warnings);
+
+        (
+            Self {
+                keymap,
+                theme,
+                icons,
+            },
+            warnings,
+        )
+    }
+}
+
+/// Folds config warnings into one status line, since only one fits.
+fn config_warning(warnings: &[String]) -> Option<Status> {
+    let first = warnings.first()?;
+    Some(Status::error(match warnings.len() {
+        1 => first.clone(),
+        n => format!("{} (and {} more)", first, n - 1),
+    }))
+}
+
+/// Where a click landed in the achievements table.
+enum TableHit {
+    Header { column: usize },
+    Row { position: usize, column: usize },
+}
+
+fn hit_test(app: &App, x: u16, y: u16) -> Option<TableHit> {
+    let inner = app.table_area.inner(Margin::new(1, 1));
+    let position = Position::new(x, y);
+    if !inner.contains(position)
+        || app
+            .details_popup
+            .is_some_and(|popup| popup.contains(position))
+    {
+        return None;
+    }
+
+    // NOTE: Mirrors how Table lays out its columns
+    let column = Layout::horizontal(ACHIEVEMENT_COLUMNS)
+        .flex(Flex::Start)
+        .spacing(COLUMN_SPACING)
+        .split(inner)
+        .iter()
+        .position(|cell| x >= cell.x && x < cell.right())?;
+
+    if y == inner.y {
+        return Some(TableHit::Header { column });
+    }
+
+    let position = app.table_state.offset() + (y - inner.y - 1) as usize;
+    (position < app.view.len()).then_some(TableHit::Row { position, column })
+}
+
+fn handle_mouse(app: &mut App, mouse: MouseEvent) {
+    if app.tab == Tab::Stats {
+        match mouse.kind {
+            MouseEventKind::ScrollDown => app.stats.next(),
+            MouseEventKind::ScrollUp => app.stats.previous(),
+            _ => {}
+        }
+        return;
+    }
+
+    match mouse.kind {
+        MouseEventKind::ScrollDown => app.next(),
+        MouseEventKind::ScrollUp => app.previous(),
+        MouseEventKind::Down(MouseButton::Left) => match hit_test(app, mouse.column, mouse.row) {
+            Some(TableHit::Header { column }) => {
+                let sort_column = match column {
+                    1 => SortColumn::Percentage,
+                    2 => SortColumn::UnlockTime,
+                    3 => SortColumn::Name,
+                    _ => return,
+                };
+                if app.sort_column == sort_column {
+                    app.toggle_sort_order();
+                } else {
+                    app.set_sort_column(sort_column);
+                }
+            }
+            Some(TableHit::Row { position, column }) => {
+                app.jump_to(position);
+                if column == COLUMN_DONE {
+                    app.toggle_selection();
+                }
+            }
+            None => {}
+        },
+        _ => {}
+    }
+}
+
+fn save_snapshot(app: &App) -> Status {
+    let snapshot = app.snapshot();
+    match snapshot::snapshot_path(&snapshot).and_then(|path| {
+        snapshot.save(&path)?;
+        Ok(path)
+    }) {
+        Ok(path) => Status::success(format!("✓ Saved snapshot to {}", path.display())),
+        Err(e) => Status::error(format!("Failed to save snapshot: {}", e)),
+    }
+}
+
+fn load_snapshot(app: &mut App) -> Status {
+    let path = match snapshot::latest_snapshot(app.app_id) {
+        Ok(Some(path)) => path,
+        Ok(None) => return Status::info(format!("No snapshots saved for App ID {}", app.app_id)),
+        Err(e) => return Status::error(e.to_string()),
+    };
+
+    match Snapshot::load(&path) {
+        Ok(snapshot) => {
+            app.select_snapshot(&snapshot);
+            let changes = app.pending_changes();
+            Status::info(format!(
+                "Loaded snapshot from {}: {} to unlock, {} to clear (Enter to apply)",
+                time::format_timestamp(snapshot.created_at),
+                changes.to_set.len(),
+                changes.to_clear.len()
+            ))
+        }
+        Err(e) => Status::error(format!("{:#}", e)),
+    }
+}
+
+fn open_app(loaded: Loaded, config: ConfigFile) -> App {
+    let mut app = App::new(loaded.achievements, loaded.id, config);
+    app.stats = StatsView::new(loaded.stats);
+    app
+}
+
 fn draw(
     f: &mut Frame,
     mut app: Option<&mut App>,
-    app_id_input: &str,
+    picker: &mut Picker,
     status: Option<&Status>,
-    editing_app_id: bool,
-    editing_search: bool,
-) {
-    let help_items = if editing_app_id {
-        vec![
-            ("0-9", "Type"),
+    mode: Mode,
+    task: Option<&Task>,
+    settings: &Settings,
+) -> Option<Placement> {
+    let Settings {
+        keymap,
+        theme,
+        icons,
+    } = settings;
+    let mut placement = None;
+    let on_stats_tab = app.as_ref().is_some_and(|app| app.tab == Tab::Stats);
+
+    let resetting = app.as_ref().is_some_and(|app| app.reset_prompt.is_some());
+
+    let reviewing = app.as_ref().is_some_and(|app| app.review.is_some());
+
+    let filtered = app.as_ref().is_some_and(|app| app.is_filtered());
+
+    let searching = app.as_ref().is_some_and(|app| app.has_search_matches());
+
+    let details_popup =
+        app.as_ref().is_some_and(|app| app.show_details) && f.area().width < DETAILS_SPLIT_WIDTH;
+
+    let help_items = if let Some(task) = task {
+        if task.cancellable {
+            fixed_help(&[("Esc", "Cancel")])
+        } else {
+            fixed_help(&[("Busy", "Waiting for Steam")])
+        }
+    } else if resetting {
+        fixed_help(&[
+            ("Any", "Type"),
+            ("Backspace", "Delete"),
+            ("Tab", "Include Achievements"),
+            ("Enter", "Reset"),
+            ("Esc", "Cancel"),
+        ])
+    } else if reviewing {
+        keymap.help(Context::Review, |action| action.label(Context::Review))
+    } else if mode == Mode::PickApp {
+        fixed_help(&[
+            ("Any", "Search"),
+            ("↑/^p", "Up"),
+            ("↓/^n", "Down"),
+            ("Backspace", "Delete"),
+            ("^u", "Clear"),
+            ("Enter", "Load"),
+            ("Esc", "Cancel"),
+        ])
+    } else if mode == Mode::Search {
+        fixed_help(&[
+            ("Any", "Type"),
             ("Backspace", "Delete"),
             ("^u", "Clear"),
-            ("Enter", "Confirm"),
+            ("Enter", "Done"),
             ("Esc/q", "Cancel"),
-        ]
-    } else if editing_search {
-        vec![
+        ])
+    } else if mode == Mode::Filter {
+        fixed_help(&[
             ("Any", "Type"),
             ("Backspace", "Delete"),
+            ("↑/↓", "Move"),
             ("^u", "Clear"),
             ("Enter", "Done"),
+            ("Esc", "Clear Filter"),
+        ])
+    } else if mode == Mode::EditStat {
+        fixed_help(&[
+            ("0-9 . - e", "Type"),
+            ("Backspace", "Delete"),
+            ("^u", "Clear"),
+            ("Enter", "Stage"),
             ("Esc/q", "Cancel"),
-        ]
+        ])
+    } else if on_stats_tab {
+        keymap.help(Context::Stats, |action| action.label(Context::Stats))
     } else {
-        vec![
-            ("j/↓", "Down"),
-            ("k/↑", "Up"),
-            ("^n/PgDn", "Page Down"),
-            ("^p/PgUp", "Page Up"),
-            ("g", "Top"),
-            ("G", "Bottom"),
-            ("Space", "Toggle"),
-            ("a", "Select All"),
-            ("d", "Deselect All"),
-            ("p/n", "Sort Column"),
-            ("o", "Sort Order"),
-            ("Enter", "Apply"),
-            ("/", "Search"),
-            ("i", "Switch App"),
-            ("Esc/q", "Quit"),
-        ]
+        let mut items = Vec::new();
+        if searching {
+            items = keymap.help(Context::Matches, |action| action.label(Context::Matches));
+        }
+        items.extend(keymap.help(Context::Achievements, |action| match action {
+            Action::Back if searching => "Clear Search",
+            Action::Back if filtered => "Clear Filter",
+            Action::Back if details_popup => "Close Details",
+            action => action.label(Context::Achievements),
+        }));
+        items
     };
 
     const BORDER_WIDTH: usize = 2;
@@ -312,25 +929,49 @@ fn draw(
         ])
         .split(f.area());
 
-    let header = Paragraph::new(if editing_app_id || app.is_none() {
-        format!("App ID: {}", app_id_input)
-    } else if editing_search {
-        format!("Search: {}", app.as_ref().unwrap().search_query)
-    } else {
-        format!(
-            "Steam Achievement Manager - App ID: {}",
-            app.as_ref().unwrap().app_id
-        )
+    let header = Paragraph::new(match app.as_ref() {
+        Some(app) if mode == Mode::Search => {
+            format!("Search: {}", app.search_query)
+        }
+        Some(app) if mode == Mode::Filter => {
+            format!("Filter: {}", app.filter_query)
+        }
+        Some(app) if mode == Mode::EditStat => format!(
+            "{}: {}",
+            app.stats.current().map_or("", |s| s.info.name.as_str()),
+            app.stats.input
+        ),
+        Some(app) if mode != Mode::PickApp => {
+            format!("Steam Achievement Manager - App ID: {}", app.app_id)
+        }
+        _ => format!("Game or App ID: {}", picker.query),
     })
-    .style(
-        Style::default()
-            .fg(Color::Cyan)
-            .add_modifier(Modifier::BOLD),
-    )
+    .style(theme.heading())
     .block(Block::default().borders(Borders::ALL));
     f.render_widget(header, chunks[0]);
 
-    if let Some(ref mut app) = app {
+    if mode == Mode::PickApp {
+        draw_picker(f, chunks[1], picker, theme);
+    } else if let Some(ref mut app) = app
+        && app.tab == Tab::Stats
+    {
+        draw_stats(f, chunks[1], app, theme);
+    } else if let Some(ref mut app) = app {
+        let (table_area, details_area) = if !app.show_details {
+            (chunks[1], None)
+        } else if details_popup {
+            let height = chunks[1].height.saturating_sub(2);
+            (
+                chunks[1],
+                Some(centered_rect(chunks[1], DETAILS_WIDTH + 16, height)),
+            )
+        } else {
+            let [table, details] =
+                Layout::horizontal([Constraint::Fill(1), Constraint::Length(DETAILS_WIDTH)])
+                    .areas(chunks[1]);
+            (table, Some(details))
+        };
+
         let sort_indicator = if app.sort_order == SortOrder::Ascending {
             "↑"
         } else {
@@ -343,6 +984,12 @@ fn draw(
             "Global".to_string()
         };
 
+        let unlocked_header = if app.sort_column == SortColumn::UnlockTime {
+            format!("Unlocked {}", sort_indicator)
+        } else {
+            "Unlocked".to_string()
+        };
+
         let name_header = if app.sort_column == SortColumn::Name {
             format!("Achievement Name {}", sort_indicator)
         } else {
@@ -350,50 +997,39 @@ fn draw(
         };
 
         let header = Row::new(vec![
-            Cell::from("Done").style(
-                Style::default()
-                    .fg(Color::Cyan)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Cell::from(percentage_header).style(
-                Style::default()
-                    .fg(Color::Cyan)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Cell::from(name_header).style(
-                Style::default()
-                    .fg(Color::Cyan)
-                    .add_modifier(Modifier::BOLD),
-            ),
+            Cell::from("Done").style(theme.heading()),
+            Cell::from(percentage_header).style(theme.heading()),
+            Cell::from(unlocked_header).style(theme.heading()),
+            Cell::from(name_header).style(theme.heading()),
+            Cell::from("Description").style(theme.heading()),
+            Cell::from("API Name").style(theme.heading()),
         ])
         .height(1);
 
+        let highlight_query = app.highlight_query();
+
         let rows: Vec<Row> = app
-            .achievements
-            .iter()
+            .visible_achievements()
             .map(|achievement| {
                 let checkbox = if achievement.selected { "[✓]" } else { "[ ]" };
 
-                let percentage_style = if achievement.percentage <= BOUND_LEGENDARY {
-                    Style::default()
-                        .fg(COLOR_LEGENDARY)
-                        .add_modifier(Modifier::BOLD)
-                } else if achievement.percentage <= BOUND_EPIC {
-                    Style::default().fg(COLOR_EPIC).add_modifier(Modifier::BOLD)
-                } else if achievement.percentage <= BOUND_RARE {
-                    Style::default().fg(COLOR_RARE)
-                } else if achievement.percentage <= BOUND_UNCOMMON {
-                    Style::default().fg(COLOR_UNCOMMON)
-                } else {
-                    Style::default().fg(COLOR_COMMON)
+                let (percentage, percentage_style) = match achievement.percentage {
+                    None => (
+                        UNKNOWN_PERCENTAGE.to_string(),
+                        Style::default().fg(theme.faint),
+                    ),
+                    Some(percentage) => (
+                        format!("{:.1}%", percentage),
+                        theme.rarity(theme.bounds.tier(percentage)),
+                    ),
                 };
 
                 let checkbox_style = match achievement.status {
-                    AchievementStatus::Failed => Style::default().fg(Color::Red),
-                    AchievementStatus::Success => Style::default().fg(Color::Green),
+                    AchievementStatus::Failed => Style::default().fg(theme.error),
+                    AchievementStatus::Success => Style::default().fg(theme.success),
                     AchievementStatus::Unchanged => {
                         if achievement.selected {
-                            Style::default().fg(Color::Green)
+                            Style::default().fg(theme.success)
                         } else {
                             Style::default()
                         }
@@ -401,15 +1037,43 @@ fn draw(
                 };
 
                 let name_style = match achievement.status {
-                    AchievementStatus::Failed => Style::default().fg(Color::Red),
-                    AchievementStatus::Success => Style::default().fg(Color::Green),
+                    AchievementStatus::Failed => Style::default().fg(theme.error),
+                    AchievementStatus::Success => Style::default().fg(theme.success),
+                    AchievementStatus::Unchanged if achievement.hidden => Style::default()
+                        .fg(theme.emphasis)
+                        .add_modifier(Modifier::ITALIC),
                     AchievementStatus::Unchanged => Style::default(),
                 };
 
+                let description = if achievement.hidden && app.hide_spoilers {
+                    Cell::from(HIDDEN_DESCRIPTION).style(
+                        Style::default()
+                            .fg(theme.faint)
+                            .add_modifier(Modifier::ITALIC),
+                    )
+                } else {
+                    Cell::from(achievement.description.clone())
+                        .style(Style::default().fg(theme.muted))
+                };
+
                 Row::new(vec![
                     Cell::from(checkbox).style(checkbox_style),
-                    Cell::from(format!("{:.1}%", achievement.percentage)).style(percentage_style),
-                    Cell::from(achievement.name.clone()).style(name_style),
+                    Cell::from(percentage).style(percentage_style),
+                    Cell::from(
+                        achievement
+                            .unlocked_at
+                            .map(time::format_timestamp)
+                            .unwrap_or_default(),
+                    )
+                    .style(Style::default().fg(theme.muted)),
+                    Cell::from(highlight_matches(
+                        achievement.title(),
+                        highlight_query,
+                        theme,
+                    ))
+                    .style(name_style),
+                    description,
+                    Cell::from(achievement.name.clone()).style(Style::default().fg(theme.faint)),
                 ])
             })
             .collect();
@@ -419,107 +1083,124 @@ fn draw(
         let achievements_percentage =
             (achievements_done as f64 / achievements_total as f64) * 100.0;
         let achievements_style = if achievements_percentage == 100.0 {
-            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+            Style::default()
+                .fg(theme.error)
+                .add_modifier(Modifier::BOLD)
         } else if achievements_percentage > 90.0 {
             Style::default()
-                .fg(COLOR_LEGENDARY)
+                .fg(theme.legendary)
                 .add_modifier(Modifier::BOLD)
         } else if achievements_percentage > 75.0 {
-            Style::default().fg(COLOR_EPIC).add_modifier(Modifier::BOLD)
+            Style::default().fg(theme.epic).add_modifier(Modifier::BOLD)
         } else if achievements_percentage > 50.0 {
-            Style::default().fg(COLOR_RARE)
+            Style::default().fg(theme.rare)
         } else if achievements_percentage > 25.0 {
-            Style::default().fg(COLOR_UNCOMMON)
+            Style::default().fg(theme.uncommon)
         } else {
-            Style::default().fg(COLOR_COMMON)
+            Style::default().fg(theme.common)
         };
 
-        let table = Table::new(
-            rows,
-            [
-                Constraint::Length(6),
-                Constraint::Length(8),
-                Constraint::Fill(1),
-            ],
-        )
-        .header(header)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title(Line::from(vec![
-                    Span::styled(
-                        " Achievements ",
-                        Style::default()
-                            .fg(Color::Magenta)
-                            .add_modifier(Modifier::BOLD),
-                    ),
-                    Span::styled(
-                        format!("{}/{} ", achievements_done, achievements_total),
-                        achievements_style,
-                    ),
-                ])),
-        )
-        .row_highlight_style(
-            Style::default()
-                .bg(Color::Rgb(0x18, 0x18, 0x18))
-                .add_modifier(Modifier::BOLD),
-        );
-        f.render_stateful_widget(table, chunks[1], &mut app.table_state);
+        let mut filters: Vec<String> = Vec::new();
+        if !app.filter_query.trim().is_empty() {
+            filters.push(format!("\"{}\"", app.filter_query.trim()));
+        }
+        if let Some(filter) = app.row_filter {
+            filters.push(filter.to_string());
+        }
+        let filter_title = if filters.is_empty() {
+            String::new()
+        } else {
+            format!(
+                "─ {} shown, filtered by {} ",
+                app.view.len(),
+                filters.join(" and ")
+            )
+        };
+
+        let table = Table::new(rows, ACHIEVEMENT_COLUMNS)
+            .header(header)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(Line::from(vec![
+                        Span::styled(" Achievements ", theme.title()),
+                        Span::styled(
+                            format!("{}/{} ", achievements_done, achievements_total),
+                            achievements_style,
+                        ),
+                        Span::styled(filter_title, Style::default().fg(theme.info)),
+                    ])),
+            )
+            .row_highlight_style(theme.highlight());
+        f.render_stateful_widget(table, table_area, &mut app.table_state);
+        app.table_area = table_area;
+
+        if let Some(area) = details_area {
+            if details_popup {
+                f.render_widget(Clear, area);
+            }
+            let icon = icons.and_then(|_| app.highlighted_icon());
+            let icon_area = draw_details(f, area, app, theme, icon.is_some());
+            if let (Some(area), Some((file, icon))) = (icon_area, icon) {
+                match icons {
+                    Some(Protocol::Blocks) => f.render_widget(IconBlocks(&icon), area),
+                    Some(Protocol::Kitty | Protocol::Sixel) => {
+                        placement = Some(Placement {
+                            file,
+                            area,
+                            screen: f.area(),
+                            icon,
+                        })
+                    }
+                    None => {}
+                }
+            }
+        }
+        app.details_popup = details_area.filter(|_| details_popup);
     } else {
         let header = Row::new(vec![
-            Cell::from("Done").style(
-                Style::default()
-                    .fg(Color::Cyan)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Cell::from("Global").style(
-                Style::default()
-                    .fg(Color::Cyan)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Cell::from("Achievement Name").style(
-                Style::default()
-                    .fg(Color::Cyan)
-                    .add_modifier(Modifier::BOLD),
-            ),
+            Cell::from("Done").style(theme.heading()),
+            Cell::from("Global").style(theme.heading()),
+            Cell::from("Unlocked").style(theme.heading()),
+            Cell::from("Achievement Name").style(theme.heading()),
+            Cell::from("Description").style(theme.heading()),
+            Cell::from("API Name").style(theme.heading()),
         ])
         .height(1);
 
-        let table = Table::new(
-            Vec::<Row>::new(),
-            [
-                Constraint::Length(6),
-                Constraint::Length(8),
-                Constraint::Fill(1),
-            ],
-        )
-        .header(header)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title(" Achievements "),
-        );
+        let table = Table::new(Vec::<Row>::new(), ACHIEVEMENT_COLUMNS)
+            .header(header)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(" Achievements "),
+            );
         f.render_widget(table, chunks[1]);
     }
 
+    let task_message;
     let editing_status_holder;
-    let (status_text, status_style) = if editing_app_id || editing_search || app.is_none() {
+    let (status_text, status_style) = if let Some(task) = task {
+        task_message = task.message();
+        (task_message.as_str(), Style::default().fg(theme.accent))
+    } else if mode != Mode::Browse || app.is_none() {
         if let Some(status) = status {
-            (status.message.as_str(), status.style())
+            (status.message.as_str(), theme.status(&status.level))
         } else {
-            editing_status_holder = if editing_search {
-                Status::info(STATUS_SEARCH_HINT.to_string())
-            } else {
-                Status::info(STATUS_APP_ID_HINT.to_string())
+            editing_status_holder = match mode {
+                Mode::Search => Status::info(STATUS_SEARCH_HINT.to_string()),
+                Mode::Filter => Status::info(STATUS_FILTER_HINT.to_string()),
+                Mode::EditStat => Status::info(STATUS_STAT_HINT.to_string()),
+                Mode::Browse | Mode::PickApp => Status::info(STATUS_APP_ID_HINT.to_string()),
             };
             (
                 editing_status_holder.message.as_str(),
-                editing_status_holder.style(),
+                theme.status(&editing_status_holder.level),
             )
         }
     } else if let Some(ref app) = app {
         if let Some(ref status) = app.status {
-            (status.message.as_str(), status.style())
+            (status.message.as_str(), theme.status(&status.level))
         } else {
             ("", Style::default())
         }
@@ -551,7 +1232,7 @@ fn draw(
             current_line_width = 0;
         }
 
-        current_line_spans.push(Span::styled(*key, Style::default().fg(Color::Yellow)));
+        current_line_spans.push(Span::styled(key.as_str(), Style::default().fg(theme.info)));
         current_line_spans.push(Span::raw(if is_last {
             format!(" {}", desc)
         } else {
@@ -568,7 +1249,482 @@ fn draw(
         Block::default()
             .borders(Borders::ALL)
             .title(" Controls ")
-            .style(Style::default().fg(Color::Gray)),
+            .style(Style::default().fg(theme.muted)),
     );
     f.render_widget(help, chunks[3]);
+
+    if let Some(app) = app {
+        if let Some(prompt) = &app.reset_prompt {
+            draw_reset_prompt(f, prompt, app.app_id, theme);
+        }
+
+        if let Some(mut review) = app.review.take() {
+            draw_review(f, app, &mut review, theme);
+            app.review = Some(review);
+        }
+    }
+
+    // Inline images would sit on top of the prompts
+    placement.filter(|_| !resetting && !reviewing)
+}
+
+/// Help for modes whose keys are fixed, like text entry.
+fn fixed_help(items: &[(&str, &'static str)]) -> Vec<(String, &'static str)> {
+    items
+        .iter()
+        .map(|&(key, label)| (key.to_string(), label))
+        .collect()
+}
+
+/// Splits `text` into spans with the characters `query` matched emphasised.
+fn highlight_matches(text: &str, query: &str, theme: &Theme) -> Line<'static> {
+    let Some(found) = fuzzy_score(text, query) else {
+        return Line::from(text.to_string());
+    };
+
+    let match_style = Style::default()
+        .fg(theme.info)
+        .add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+
+    let span = |text: String, matched: bool| {
+        if matched {
+            Span::styled(text, match_style)
+        } else {
+            Span::raw(text)
+        }
+    };
+
+    let mut spans: Vec<Span> = Vec::new();
+    let mut run = String::new();
+    let mut run_matched = false;
+
+    for (i, c) in text.chars().enumerate() {
+        let matched = found.positions.binary_search(&i).is_ok();
+        if matched != run_matched && !run.is_empty() {
+            spans.push(span(std::mem::take(&mut run), run_matched));
+        }
+        run_matched = matched;
+        run.push(c);
+    }
+
+    if !run.is_empty() {
+        spans.push(span(run, run_matched));
+    }
+
+    Line::from(spans)
+}
+
+fn centered_rect(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+fn draw_reset_prompt(f: &mut Frame, prompt: &ResetPrompt, app_id: u32, theme: &Theme) {
+    let area = centered_rect(f.area(), 64, 8);
+
+    let checkbox = if prompt.include_achievements {
+        "[✓]"
+    } else {
+        "[ ]"
+    };
+
+    let input_style = if prompt.is_confirmed() {
+        Style::default()
+            .fg(theme.error)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(theme.info)
+    };
+
+    let lines = vec![
+        Line::from(format!(
+            "This wipes every stat for App ID {} and cannot be undone.",
+            app_id
+        )),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled(checkbox, Style::default().fg(theme.accent)),
+            Span::raw(" Include achievements"),
+        ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::raw(format!("Type {} to confirm: ", RESET_CONFIRMATION)),
+            Span::styled(prompt.input.clone(), input_style),
+        ]),
+    ];
+
+    let popup = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(" Reset All Stats ")
+            .style(Style::default().fg(theme.error)),
+    );
+
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
+
+fn draw_review(f: &mut Frame, app: &App, review: &mut Review, theme: &Theme) {
+    let height = (review.entries.len() as u16).saturating_add(3);
+    let area = centered_rect(f.area(), 100, height.min(f.area().height.saturating_sub(4)));
+
+    let mut to_set = 0;
+    let mut to_clear = 0;
+
+    let rows: Vec<Row> = review
+        .entries
+        .iter()
+        .map(|entry| {
+            let included = app.is_pending(&entry.name);
+            match (included, entry.unlock) {
+                (true, true) => to_set += 1,
+                (true, false) => to_clear += 1,
+                (false, _) => {}
+            }
+
+            let (action, action_style) = if entry.unlock {
+                ("Unlock", Style::default().fg(theme.success))
+            } else {
+                (
+                    "Clear",
+                    Style::default()
+                        .fg(theme.error)
+                        .add_modifier(Modifier::BOLD),
+                )
+            };
+
+            let title = app
+                .achievements
+                .iter()
+                .find(|a| a.name == entry.name)
+                .map_or(entry.name.as_str(), |a| a.title());
+
+            let row = Row::new(vec![
+                Cell::from(if included { "[✓]" } else { "[ ]" }),
+                Cell::from(action).style(action_style),
+                Cell::from(title.to_string()),
+                Cell::from(entry.name.clone()).style(Style::default().fg(theme.faint)),
+            ]);
+
+            if included {
+                row
+            } else {
+                row.style(
+                    Style::default()
+                        .fg(theme.faint)
+                        .add_modifier(Modifier::CROSSED_OUT),
+                )
+            }
+        })
+        .collect();
+
+    let title = Line::from(vec![
+        Span::raw(" Review: "),
+        Span::styled(
+            format!("{} to unlock", to_set),
+            Style::default().fg(theme.success),
+        ),
+        Span::raw(", "),
+        Span::styled(
+            format!("{} to clear", to_clear),
+            Style::default()
+                .fg(theme.error)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(" "),
+    ]);
+
+    let header_style = theme.heading();
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(3),
+            Constraint::Length(6),
+            Constraint::Fill(1),
+            Constraint::Fill(1),
+        ],
+    )
+    .header(
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from("Action").style(header_style),
+            Cell::from("Achievement Name").style(header_style),
+            Cell::from("API Name").style(header_style),
+        ])
+        .height(1),
+    )
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .title_bottom(" Enter to apply, Esc to go back ")
+            .style(Style::default().fg(theme.info)),
+    )
+    .row_highlight_style(theme.highlight());
+
+    f.render_widget(Clear, area);
+    f.render_stateful_widget(table, area, &mut review.table_state);
+}
+
+/// Everything known about the highlighted achievement. Returns where its
+/// icon goes if `has_icon` and there is room for it.
+fn draw_details(
+    f: &mut Frame,
+    area: Rect,
+    app: &App,
+    theme: &Theme,
+    has_icon: bool,
+) -> Option<Rect> {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(Span::styled(" Details ", theme.title()));
+
+    let Some(achievement) = app.highlighted() else {
+        f.render_widget(
+            Paragraph::new("No achievement selected")
+                .style(Style::default().fg(theme.faint))
+                .block(block),
+            area,
+        );
+        return None;
+    };
+
+    let field = |label: &'static str, value: Span<'static>| {
+        Line::from(vec![
+            Span::styled(format!("{:<12}", label), theme.heading()),
+            value,
+        ])
+    };
+
+    let description = if achievement.hidden && app.hide_spoilers {
+        Span::styled(
+            HIDDEN_DESCRIPTION,
+            Style::default()
+                .fg(theme.faint)
+                .add_modifier(Modifier::ITALIC),
+        )
+    } else if achievement.description.is_empty() {
+        Span::styled("None", Style::default().fg(theme.faint))
+    } else {
+        Span::raw(achievement.description.clone())
+    };
+
+    let percentage = match achievement.percentage {
+        Some(percentage) => {
+            let rarity = theme.bounds.tier(percentage);
+            Span::styled(
+                format!("{:.1}% ({})", percentage, rarity),
+                theme.rarity(rarity),
+            )
+        }
+        None => Span::styled(UNKNOWN_PERCENTAGE, Style::default().fg(theme.faint)),
+    };
+
+    let unlocked = match achievement.unlocked_at {
+        Some(at) => Span::raw(time::format_timestamp(at)),
+        None if achievement.unlocked => Span::raw("Yes"),
+        None => Span::styled("Locked", Style::default().fg(theme.faint)),
+    };
+
+    let last_apply = match achievement.status {
+        AchievementStatus::Unchanged => {
+            Span::styled("Not applied yet", Style::default().fg(theme.faint))
+        }
+        AchievementStatus::Success => {
+            Span::styled("✓ Succeeded", Style::default().fg(theme.success))
+        }
+        AchievementStatus::Failed => Span::styled("✗ Failed", Style::default().fg(theme.error)),
+    };
+
+    let lines = vec![
+        Line::from(Span::styled(
+            achievement.title().to_string(),
+            Style::default().add_modifier(Modifier::BOLD),
+        )),
+        Line::from(description),
+        Line::from(""),
+        field("API Name", Span::raw(achievement.name.clone())),
+        field(
+            "Hidden",
+            Span::raw(if achievement.hidden { "Yes" } else { "No" }),
+        ),
+        field("Global", percentage),
+        field("Unlocked", unlocked),
+        field("Last Apply", last_apply),
+    ];
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let icon_area =
+        (has_icon && inner.width >= ICON_COLS && inner.height >= ICON_ROWS + DETAILS_MIN_TEXT_ROWS)
+            .then(|| {
+                let [icon] = Layout::horizontal([Constraint::Length(ICON_COLS)])
+                    .flex(Flex::Center)
+                    .areas(inner.resize(Size::new(inner.width, ICON_ROWS)));
+                icon
+            });
+    let text_area = match icon_area {
+        Some(_) => Rect {
+            y: inner.y + ICON_ROWS + 1,
+            height: inner.height - ICON_ROWS - 1,
+            ..inner
+        },
+        None => inner,
+    };
+
+    f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), text_area);
+    icon_area
+}
+
+fn draw_stats(f: &mut Frame, area: Rect, app: &mut App, theme: &Theme) {
+    let header_style = theme.heading();
+
+    let header = Row::new(vec![
+        Cell::from("Stat Name").style(header_style),
+        Cell::from("Type").style(header_style),
+        Cell::from("Value").style(header_style),
+        Cell::from("New Value").style(header_style),
+        Cell::from("Range").style(header_style),
+        Cell::from("API Name").style(header_style),
+    ])
+    .height(1);
+
+    let rows: Vec<Row> = app
+        .stats
+        .items
+        .iter()
+        .map(|item| {
+            let name_style = match item.status {
+                AchievementStatus::Failed => Style::default().fg(theme.error),
+                AchievementStatus::Success => Style::default().fg(theme.success),
+                AchievementStatus::Unchanged => Style::default(),
+            };
+
+            let title = if item.info.display_name.is_empty() {
+                item.info.name.clone()
+            } else {
+                item.info.display_name.clone()
+            };
+
+            let range = match (item.info.min, item.info.max) {
+                (Some(min), Some(max)) => format!("{} – {}", min, max),
+                (Some(min), None) => format!("≥ {}", min),
+                (None, Some(max)) => format!("≤ {}", max),
+                (None, None) => String::new(),
+            };
+
+            let range = if item.info.increment_only {
+                format!("{} (increment only)", range).trim().to_string()
+            } else {
+                range
+            };
+
+            Row::new(vec![
+                Cell::from(title).style(name_style),
+                Cell::from(item.info.kind.to_string()).style(Style::default().fg(theme.muted)),
+                Cell::from(item.info.value.to_string()),
+                Cell::from(item.pending.map(|v| v.to_string()).unwrap_or_default())
+                    .style(Style::default().fg(theme.info)),
+                Cell::from(range).style(Style::default().fg(theme.muted)),
+                Cell::from(item.info.name.clone()).style(Style::default().fg(theme.faint)),
+            ])
+        })
+        .collect();
+
+    let mut title = vec![Span::styled(" Stats ", theme.title())];
+
+    let pending = app.stats.pending_count();
+    if pending > 0 {
+        title.push(Span::styled(
+            format!("{} pending ", pending),
+            Style::default().fg(theme.info),
+        ));
+    }
+
+    if let Some(error) = &app.stats.error {
+        title.push(Span::styled(
+            format!("{} ", error),
+            Style::default().fg(theme.error),
+        ));
+    }
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Fill(2),
+            Constraint::Length(9),
+            Constraint::Length(12),
+            Constraint::Length(12),
+            Constraint::Fill(2),
+            Constraint::Fill(2),
+        ],
+    )
+    .header(header)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(Line::from(title)),
+    )
+    .row_highlight_style(theme.highlight());
+    f.render_stateful_widget(table, area, &mut app.stats.table_state);
+}
+
+fn draw_picker(f: &mut Frame, area: Rect, picker: &mut Picker, theme: &Theme) {
+    let header_style = theme.heading();
+
+    let header = Row::new(vec![
+        Cell::from("App ID").style(header_style),
+        Cell::from("Game").style(header_style),
+        Cell::from("Install Dir").style(header_style),
+    ])
+    .height(1);
+
+    let rows: Vec<Row> = picker
+        .visible_apps()
+        .map(|app| {
+            Row::new(vec![
+                Cell::from(app.id.to_string()).style(Style::default().fg(theme.muted)),
+                Cell::from(app.name.clone()),
+                Cell::from(app.install_dir.display().to_string())
+                    .style(Style::default().fg(theme.faint)),
+            ])
+        })
+        .collect();
+
+    let mut title = vec![
+        Span::styled(" Games ", theme.title()),
+        Span::raw(format!("{}/{} ", picker.matches.len(), picker.apps.len())),
+    ];
+
+    if let Some(error) = &picker.error {
+        title.push(Span::styled(
+            format!("{} ", error),
+            Style::default().fg(theme.error),
+        ));
+    }
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(10),
+            Constraint::Fill(2),
+            Constraint::Fill(3),
+        ],
+    )
+    .header(header)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(Line::from(title)),
+    )
+    .row_highlight_style(theme.highlight());
+    f.render_stateful_widget(table, area, &mut picker.table_state);
 }
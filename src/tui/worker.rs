@@ -0,0 +1,351 @@
+use crate::steam::{
+    AchievementData, Changes, ProcessResult, StatInfo, StatValue, StatsBackend,
+    process_achievements, process_stats,
+};
+use anyhow::Result;
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const SPINNER_FRAMES: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+const SPINNER_INTERVAL: Duration = Duration::from_millis(80);
+
+/// How often, and for how long after a load, to check for late global percentages.
+const PERCENTAGES_POLL_INTERVAL: Duration = Duration::from_millis(250);
+const PERCENTAGES_WAIT: Duration = Duration::from_secs(30);
+
+/// Ticket for outcomes nobody is waiting on, like late global percentages.
+const BACKGROUND: u64 = 0;
+
+/// A unit of Steam work, run off the UI thread.
+pub enum Job {
+    Load(u32),
+    Apply {
+        id: u32,
+        changes: Changes,
+    },
+    ApplyStats {
+        id: u32,
+        changes: Vec<(String, StatValue)>,
+    },
+    Reset {
+        id: u32,
+        include_achievements: bool,
+    },
+}
+
+impl Job {
+    fn label(&self) -> String {
+        match self {
+            Job::Load(id) => format!("Loading App ID {}", id),
+            Job::Apply { .. } => "Applying achievement changes".to_string(),
+            Job::ApplyStats { .. } => "Storing stats".to_string(),
+            Job::Reset { id, .. } => format!("Resetting App ID {}", id),
+        }
+    }
+}
+
+pub struct Loaded {
+    pub id: u32,
+    pub achievements: AchievementData,
+    pub stats: Result<Vec<StatInfo>>,
+}
+
+/// Achievements sent to Steam in one store, and how that went.
+pub struct Batch {
+    pub names: Vec<String>,
+    pub results: Result<Vec<ProcessResult>, String>,
+}
+
+pub enum Outcome {
+    Loaded(Result<Loaded>),
+    Applied {
+        unlocked: Batch,
+        cleared: Batch,
+    },
+    StatsApplied(Result<Vec<ProcessResult>, String>),
+    Reset {
+        include_achievements: bool,
+        result: Result<Loaded>,
+    },
+    Percentages {
+        id: u32,
+        percentages: HashMap<String, f32>,
+    },
+}
+
+impl Outcome {
+    fn loaded(&self) -> Option<&Loaded> {
+        match self {
+            Outcome::Loaded(Ok(loaded)) => Some(loaded),
+            Outcome::Reset {
+                result: Ok(loaded), ..
+            } => Some(loaded),
+            _ => None,
+        }
+    }
+}
+
+/// The job the UI is currently waiting on.
+pub struct Task {
+    ticket: u64,
+    pub label: String,
+    pub started: Instant,
+    pub cancellable: bool,
+}
+
+impl Task {
+    pub fn spinner(&self) -> char {
+        let frame = self.started.elapsed().as_millis() / SPINNER_INTERVAL.as_millis();
+        SPINNER_FRAMES[frame as usize % SPINNER_FRAMES.len()]
+    }
+
+    pub fn message(&self) -> String {
+        let mut message = format!(
+            "{} {}... {:.1}s",
+            self.spinner(),
+            self.label,
+            self.started.elapsed().as_secs_f32()
+        );
+        if self.cancellable {
+            message.push_str(" (Esc to cancel)");
+        }
+        message
+    }
+}
+
+type Jobs = Sender<(u64, Job)>;
+type Outcomes = Receiver<(u64, Outcome)>;
+
+/// Makes a backend for a worker thread, once at the start and again each
+/// time a cancelled job leaves the old thread stuck.
+type NewBackend = Box<dyn Fn() -> Box<dyn StatsBackend + Send>>;
+
+/// Owns the backend on a background thread so slow Steam calls never block
+/// drawing or input. Jobs run one at a time, in the order they were submitted.
+pub struct Worker {
+    new_backend: NewBackend,
+    jobs: Jobs,
+    outcomes: Outcomes,
+    next_ticket: u64,
+    task: Option<Task>,
+}
+
+impl Worker {
+    pub fn spawn(new_backend: impl Fn() -> Box<dyn StatsBackend + Send> + 'static) -> Self {
+        let (jobs, outcomes) = start(new_backend());
+
+        Self {
+            new_backend: Box::new(new_backend),
+            jobs,
+            outcomes,
+            next_ticket: 0,
+            task: None,
+        }
+    }
+
+    pub fn submit(&mut self, job: Job) {
+        self.next_ticket += 1;
+        self.task = Some(Task {
+            ticket: self.next_ticket,
+            label: job.label(),
+            started: Instant::now(),
+            cancellable: matches!(job, Job::Load(_)),
+        });
+
+        // NOTE: The thread only exits once this sender is dropped
+        let _ = self.jobs.send((self.next_ticket, job));
+    }
+
+    pub fn task(&self) -> Option<&Task> {
+        self.task.as_ref()
+    }
+
+    /// Stops waiting on the current job if it can be abandoned. Steam can't be
+    /// interrupted, so the job is left to finish on the old thread, which
+    /// then exits without reporting, and later jobs go to a fresh thread with
+    /// its own backend.
+    pub fn cancel(&mut self) -> Option<Task> {
+        if !self.task.as_ref().is_some_and(|task| task.cancellable) {
+            return None;
+        }
+
+        (self.jobs, self.outcomes) = start((self.new_backend)());
+        self.task.take()
+    }
+
+    /// Returns the outcome of the current job once it has finished, or any
+    /// background result that has arrived since.
+    pub fn poll(&mut self) -> Option<Outcome> {
+        while let Ok((ticket, outcome)) = self.outcomes.try_recv() {
+            if ticket == BACKGROUND {
+                return Some(outcome);
+            }
+
+            if self.task.as_ref().is_some_and(|task| task.ticket == ticket) {
+                self.task = None;
+                return Some(outcome);
+            }
+        }
+        None
+    }
+}
+
+/// Runs jobs against `backend` on a new thread until the job sender or the
+/// outcome receiver is dropped.
+fn start(mut backend: Box<dyn StatsBackend + Send>) -> (Jobs, Outcomes) {
+    let (jobs, job_receiver) = mpsc::channel::<(u64, Job)>();
+    let (outcome_sender, outcomes) = mpsc::channel();
+
+    thread::spawn(move || {
+        // App still missing global percentages, and when to give up on them
+        let mut awaiting: Option<(u32, Instant)> = None;
+
+        loop {
+            let received = match awaiting {
+                Some(_) => job_receiver.recv_timeout(PERCENTAGES_POLL_INTERVAL),
+                None => job_receiver
+                    .recv()
+                    .map_err(|_| RecvTimeoutError::Disconnected),
+            };
+
+            match received {
+                Ok((ticket, job)) => {
+                    let outcome = run(backend.as_mut(), job);
+                    if let Some(loaded) = outcome.loaded() {
+                        awaiting = loaded
+                            .achievements
+                            .achievements
+                            .iter()
+                            .any(|a| a.percentage.is_none())
+                            .then(|| (loaded.id, Instant::now() + PERCENTAGES_WAIT));
+                    }
+                    if outcome_sender.send((ticket, outcome)).is_err() {
+                        break;
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+
+            if let Some((id, deadline)) = awaiting {
+                match backend.global_percentages(id) {
+                    Ok(Some(percentages)) => {
+                        awaiting = None;
+                        let outcome = Outcome::Percentages { id, percentages };
+                        if outcome_sender.send((BACKGROUND, outcome)).is_err() {
+                            break;
+                        }
+                    }
+                    Ok(None) if Instant::now() < deadline => {}
+                    _ => awaiting = None,
+                }
+            }
+        }
+    });
+
+    (jobs, outcomes)
+}
+
+fn run(backend: &mut dyn StatsBackend, job: Job) -> Outcome {
+    match job {
+        Job::Load(id) => Outcome::Loaded(load(backend, id)),
+        Job::Apply { id, changes } => Outcome::Applied {
+            unlocked: batch(backend, id, changes.to_set, false),
+            cleared: batch(backend, id, changes.to_clear, true),
+        },
+        Job::ApplyStats { id, changes } => {
+            let (results, stored) = process_stats(backend, id, changes);
+            Outcome::StatsApplied(saved(results, stored))
+        }
+        Job::Reset {
+            id,
+            include_achievements,
+        } => Outcome::Reset {
+            include_achievements,
+            result: backend
+                .reset_all_stats(id, include_achievements)
+                .and_then(|()| load(backend, id)),
+        },
+    }
+}
+
+fn load(backend: &mut dyn StatsBackend, id: u32) -> Result<Loaded> {
+    let achievements = backend.get_achievements(id)?;
+    let stats = backend.get_stats(id);
+    Ok(Loaded {
+        id,
+        achievements,
+        stats,
+    })
+}
+
+pub(super) fn batch(
+    backend: &mut dyn StatsBackend,
+    id: u32,
+    names: Vec<String>,
+    clear: bool,
+) -> Batch {
+    let results = if names.is_empty() {
+        Ok(Vec::new())
+    } else {
+        let (results, stored) = process_achievements(backend, id, names.clone(), clear);
+        saved(results, stored)
+    };
+
+    Batch { names, results }
+}
+
+/// The results if they were stored. Nothing is saved when the store fails,
+/// so the caller treats every change as failed then.
+fn saved(results: Vec<ProcessResult>, stored: Result<()>) -> Result<Vec<ProcessResult>, String> {
+    stored.map(|()| results).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::steam::MockBackend;
+
+    const FIXTURE: &str = r#"{ "apps": {
+        "480": { "achievements": [{ "name": "ACH_SLOW", "unlocked": false }], "delay_ms": 60000 },
+        "620": { "achievements": [{ "name": "ACH_PORTAL", "unlocked": false }] }
+    } }"#;
+
+    fn spawn() -> Worker {
+        Worker::spawn(|| Box::new(serde_json::from_str::<MockBackend>(FIXTURE).unwrap()))
+    }
+
+    #[test]
+    fn cancel_frees_the_worker_for_later_jobs() {
+        let mut worker = spawn();
+        worker.submit(Job::Load(480));
+        assert!(worker.cancel().is_some());
+        worker.submit(Job::Load(620));
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let loaded = loop {
+            assert!(
+                Instant::now() < deadline,
+                "job stuck behind the cancelled load"
+            );
+            match worker.poll() {
+                Some(Outcome::Loaded(result)) => break result.unwrap(),
+                Some(_) => {}
+                None => thread::sleep(Duration::from_millis(10)),
+            }
+        };
+        assert_eq!(loaded.id, 620);
+    }
+
+    #[test]
+    fn only_loads_can_be_cancelled() {
+        let mut worker = spawn();
+        worker.submit(Job::Reset {
+            id: 620,
+            include_achievements: false,
+        });
+        assert!(worker.cancel().is_none());
+    }
+}